@@ -1,16 +1,43 @@
 use ggez::audio;
+use ggez::graphics;
 use ggez::{Context, GameResult};
 
+use tetris::items::*;
+
+use std::collections::HashMap;
+
 pub struct Assets {
-    pub theme_song: audio::Source
+    pub theme_song: audio::Source,
+    item_images: HashMap<ItemKind, graphics::Image>,
+    item_sounds: HashMap<ItemKind, audio::Source>
 }
 
 impl Assets {
     pub fn new(ctx: &mut Context) -> GameResult<Assets> {
         let theme_song = audio::Source::new(ctx, "/tetris_theme_song.mp3")?;
 
+        let mut item_images = HashMap::new();
+        let mut item_sounds = HashMap::new();
+        for kind in ItemKind::all() {
+            // The bomb keeps its original sound file
+            let sound = if kind == ItemKind::Bomb { String::from("/bomb.ogg") } else { format!("/{}.wav", kind) };
+
+            item_images.insert(kind, graphics::Image::new(ctx, format!("/{}.png", kind))?);
+            item_sounds.insert(kind, audio::Source::new(ctx, sound)?);
+        }
+
         Ok(Assets {
-            theme_song
+            theme_song,
+            item_images,
+            item_sounds
         })
     }
+
+    pub fn item_image(&self, kind: ItemKind) -> &graphics::Image {
+        &self.item_images[&kind]
+    }
+
+    pub fn item_sound(&mut self, kind: ItemKind) -> &mut audio::Source {
+        self.item_sounds.get_mut(&kind).unwrap()
+    }
 }
\ No newline at end of file
@@ -1,17 +1,11 @@
-use crate::constants::*;
 use crate::configuration::*;
+use crate::board::*;
+use crate::rotation::*;
 
-use std::mem;
-use ggez::graphics::{self, Rect, Color, MeshBuilder, DrawMode, DrawParam};
-use ggez::mint::Point2;
-use ggez::Context;
-use ggez::GameResult;
+use ggez::graphics::Color;
 
-use rand::{
-    distributions::{Distribution, Standard},
-    Rng,
-    thread_rng
-};
+use std::fmt;
+use std::str::FromStr;
 
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum BlockType {
@@ -26,219 +20,193 @@ pub enum BlockType {
     BigZ
 }
 
-impl Distribution<BlockType> for Standard {
-    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> BlockType {
-        match rng.gen_range(0..=8) {
-            0 => BlockType::I,
-            1 => BlockType::J,
-            2 => BlockType::L,
-            3 => BlockType::O,
-            4 => BlockType::S,
-            5 => BlockType::T,
-            6 => BlockType::Z,
-            7 => BlockType::Plus,
-            8 => BlockType::BigZ,
-            _ => BlockType::I
+/// Which pieces the randomizer may deal: the seven classic tetrominoes, or those plus `Plus` and `BigZ`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PieceSet {
+    Classic,
+    Extended
+}
+
+impl FromStr for PieceSet {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "classic" => Ok(PieceSet::Classic),
+            "extended" => Ok(PieceSet::Extended),
+            _ => Err(format!("unknown piece set {}", value))
         }
     }
 }
 
-pub struct Square {
-    pub row: f32,
-    pub column: f32,
-    pub color: Color,
-    pub component: Rect
-}
+impl fmt::Display for PieceSet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let value = match self {
+            PieceSet::Classic => "classic",
+            PieceSet::Extended => "extended"
+        };
 
-impl Square {
-    pub fn new(row: f32, column: f32, color: Color) -> Self {
-        Square {
-            row,
-            column,
-            color,
-            component: Rect::new(BORDER_SIZE, BORDER_SIZE, SQUARE_SIZE - (BORDER_SIZE * 2.0), SQUARE_SIZE - (BORDER_SIZE * 2.0))
-        }
+        write!(f, "{}", value)
     }
+}
 
-    pub fn draw(&self, ctx: &mut Context) -> GameResult<()> {
-        let mut mesh = MeshBuilder::new();
-        mesh.rectangle(DrawMode::fill(), self.component, self.color);
+impl PieceSet {
+    pub fn pieces(&self) -> Vec<BlockType> {
+        let mut pieces = vec!(BlockType::I, BlockType::J, BlockType::L, BlockType::O, BlockType::S, BlockType::T, BlockType::Z);
+        if *self == PieceSet::Extended {
+            pieces.push(BlockType::Plus);
+            pieces.push(BlockType::BigZ);
+        }
 
-        let mesh = &mesh.build(ctx).unwrap();
-        graphics::draw(ctx, mesh, DrawParam {
-            dest: Point2 {
-                x: self.column * SQUARE_SIZE + ENTRY_POINT.0,
-                y: self.row * SQUARE_SIZE + ENTRY_POINT.1,
-            },
-            .. Default::default()
-        }).unwrap();
-        
-        Ok(())
+        pieces
     }
 }
 
 #[derive(Clone)]
 pub struct Block {
-    pub positions: Vec<(f32, f32)>,
-    pub translate: (f32, f32),
+    pub positions: Vec<(i32, i32)>,
+    pub translate: (i32, i32),
     configuration: Configuration,
     block_type: BlockType,
+    rotation_state: RotationState,
     color: Color
 }
 
 impl Block {
-    pub fn new(block_type: BlockType, configuration: Configuration) -> Self {
-        if configuration.classic_mode() && (block_type == BlockType::BigZ || block_type == BlockType::Plus) {
-            return Block::new(rand::random(), configuration);
-        }
-
-        let positions: Vec<(f32, f32)>;
-        match block_type {
-            BlockType::I => positions = vec!((0.0, 0.0), (1.0, 0.0), (2.0, 0.0), (3.0, 0.0)),
-            BlockType::J => positions = vec!((0.0, 1.0), (1.0, 1.0), (2.0, 1.0), (2.0, 0.0)),
-            BlockType::L => positions = vec!((0.0, 0.0), (1.0, 0.0), (2.0, 0.0), (2.0, 1.0)),
-            BlockType::O => positions = vec!((0.0, 0.0), (0.0, 1.0), (1.0, 0.0), (1.0, 1.0)),
-            BlockType::S => positions = vec!((0.0, 1.0), (0.0, 2.0), (1.0, 0.0), (1.0, 1.0)),
-            BlockType::T => positions = vec!((0.0, 0.0), (0.0, 1.0), (0.0, 2.0), (1.0, 1.0)),
-            BlockType::Z => positions = vec!((0.0, 0.0), (0.0, 1.0), (1.0, 1.0), (1.0, 2.0)),
-            BlockType::Plus => positions = vec!((0.0, 1.0), (1.0, 0.0), (1.0, 1.0), (1.0, 2.0), (2.0, 1.0)),
-            BlockType::BigZ => positions = vec!((0.0, 0.0), (0.0, 1.0), (1.0, 1.0), (2.0, 1.0), (2.0, 2.0))
-        }
-        
-        let mut rng = thread_rng();
-        let random_color = Color::new(rng.gen_range(0.5..1.0), rng.gen_range(0.5..1.0), rng.gen_range(0.5..1.0), rng.gen_range(0.5..1.0));
+    pub fn new(block_type: BlockType, configuration: Configuration, color: Color) -> Self {
+        let positions = Block::shape(block_type);
 
         Block {
             block_type,
+            rotation_state: RotationState::Spawn,
             positions,
             configuration,
-            color: random_color,
-            translate: (0.0, 0.0)
+            color,
+            translate: (0, 0)
+        }
+    }
+
+    /// Every block type has its own color, so squares of one color all come from the same type of piece.
+    pub fn color_of(block_type: BlockType) -> Color {
+        match block_type {
+            BlockType::I => Color::from_rgb(0, 240, 240),
+            BlockType::J => Color::from_rgb(40, 80, 240),
+            BlockType::L => Color::from_rgb(240, 160, 0),
+            BlockType::O => Color::from_rgb(240, 240, 0),
+            BlockType::S => Color::from_rgb(0, 240, 0),
+            BlockType::T => Color::from_rgb(160, 0, 240),
+            BlockType::Z => Color::from_rgb(240, 0, 0),
+            BlockType::Plus => Color::from_rgb(240, 120, 200),
+            BlockType::BigZ => Color::from_rgb(180, 180, 180)
+        }
+    }
+
+    /// The cells of `block_type` in its spawn orientation, as `(x, y)` inside its bounding box.
+    pub fn shape(block_type: BlockType) -> Vec<(i32, i32)> {
+        match block_type {
+            BlockType::I => vec!((0, 1), (1, 1), (2, 1), (3, 1)),
+            BlockType::J => vec!((0, 0), (0, 1), (1, 1), (2, 1)),
+            BlockType::L => vec!((2, 0), (0, 1), (1, 1), (2, 1)),
+            BlockType::O => vec!((0, 0), (0, 1), (1, 0), (1, 1)),
+            BlockType::S => vec!((1, 0), (2, 0), (0, 1), (1, 1)),
+            BlockType::T => vec!((1, 0), (0, 1), (1, 1), (2, 1)),
+            BlockType::Z => vec!((0, 0), (1, 0), (1, 1), (2, 1)),
+            BlockType::Plus => vec!((1, 0), (0, 1), (1, 1), (2, 1), (1, 2)),
+            BlockType::BigZ => vec!((0, 0), (0, 1), (1, 1), (2, 1), (2, 2))
         }
     }
 
-    pub fn translate(&mut self, x: f32, y: f32) {
+    /// The side of the square bounding box the block rotates in.
+    pub fn box_size(block_type: BlockType) -> i32 {
+        match block_type {
+            BlockType::I => 4,
+            BlockType::O => 2,
+            _ => 3
+        }
+    }
+
+    pub fn translate(&mut self, x: i32, y: i32) {
         self.translate.0 += x;
         self.translate.1 += y;
     }
 
-    pub fn draw(&self, ctx: &mut Context, viewing_area_row: i32) -> GameResult<()> {
-        for pos in self.positions.iter() {
-            let row = (pos.1 + self.translate.1).round();
-
-            if row < (viewing_area_row + self.configuration.viewing_area_rows_count()) as f32 &&
-               row >= viewing_area_row as f32 {
-
-                let mut mesh = MeshBuilder::new();
-                mesh.rectangle(DrawMode::fill(), Square::new(0.0, 0.0, self.color).component, self.color);
-                
-                let mesh = &mesh.build(ctx).unwrap();
-                graphics::draw(ctx, mesh, DrawParam {
-                    dest: Point2 {
-                        x: (pos.0 + self.translate.0) * SQUARE_SIZE + ENTRY_POINT.0,
-                        y: (pos.1 + self.translate.1) * SQUARE_SIZE + ENTRY_POINT.1,
-                    },
-                    .. Default::default()
-                }).unwrap();
-            }
-        }
-        
-        Ok(())
+    pub fn color(&self) -> Color {
+        self.color
     }
 
-    pub fn to_squares(&self) -> Vec<Square> {
-        let mut squares: Vec<Square> = Vec::new();
-        for pos in self.positions.iter() {
-            let row = (pos.1 + self.translate.1).round();
-            let column = (pos.0 + self.translate.0).round();
+    pub fn block_type(&self) -> BlockType {
+        self.block_type
+    }
 
-            squares.push(Square::new(row, column, self.color));
-        }
+    pub fn rotation_state(&self) -> RotationState {
+        self.rotation_state
+    }
 
-        squares
-    }
-
-    pub fn rotate(&mut self) {
-        match self.block_type {
-            BlockType::J | BlockType::L | BlockType::S | BlockType::T | BlockType::Z | BlockType::BigZ => self.rotate_easy_blocks(),
-            BlockType::I => {
-                for pos in self.positions.iter_mut() {
-                    mem::swap(&mut pos.0, &mut pos.1);
-                }
-
-                if self.positions[1].0 == 0.0 {
-                    self.translate(1.0, -1.0);
-                } 
-                else {
-                    self.translate(0.0, 1.0);
-                }
-            },
-            _ => () //Do nothing
+    /// The positions of the block in `state`, found by turning the spawn shape clockwise inside its box.
+    pub fn positions_for(&self, state: RotationState) -> Vec<(i32, i32)> {
+        let size = Block::box_size(self.block_type);
+        let mut positions = Block::shape(self.block_type);
+
+        if self.block_type == BlockType::O {
+            return positions;
         }
 
-        let mut move_left: f32 = 0.0;
-        for pos in self.positions.iter() {
-            if pos.0 + self.translate.0 + 1.0 > BOARD_WIDTH / SQUARE_SIZE {
-                if move_left < pos.0 + self.translate.0 + 1.0 - BOARD_WIDTH / SQUARE_SIZE {
-                    move_left = pos.0 + self.translate.0 + 1.0 - BOARD_WIDTH / SQUARE_SIZE;
-                }
+        for _ in 0..state.index() {
+            for pos in positions.iter_mut() {
+                *pos = (size - 1 - pos.1, pos.0);
             }
         }
 
-        self.translate.0 -= move_left;
+        positions
     }
 
-    fn rotate_easy_blocks(&mut self) {
-        let mut new_positions: Vec<(f32, f32)> = Vec::new();
-        for pos in self.positions.iter_mut() {
-            mem::swap(&mut pos.0, &mut pos.1);
-        }
+    /// Puts the block back in its spawn orientation at the entry point.
+    pub fn reset(&mut self) {
+        self.positions = Block::shape(self.block_type);
+        self.rotation_state = RotationState::Spawn;
+        self.translate = (0, 0);
+    }
 
-        let diagonal_matrix = [(0.0, 2.0), (1.0, 1.0), (2.0, 0.0)];
+    /// Moves the block to a placement found by `rotation::try_rotate`.
+    pub fn place(&mut self, placement: Placement) {
+        self.positions = self.positions_for(placement.state);
+        self.rotation_state = placement.state;
+        self.translate = placement.translate;
+    }
 
-        for pos in self.positions.iter() {
-            for value in diagonal_matrix.iter() {
-                if pos.1 == value.1 {
-                    new_positions.push((pos.0, value.0));
-                }
-            }
-        }
+    pub fn configuration(&self) -> &Configuration {
+        &self.configuration
+    }
 
-        self.positions = new_positions;
+    /// The board cells covered by the block as `(row, column)`.
+    pub fn to_cells(&self) -> Vec<(i32, i32)> {
+        self.positions.iter().map(|pos| (pos.1 + self.translate.1, pos.0 + self.translate.0)).collect()
     }
 
-    fn should_stop(&self, squares: &Vec<Square>) -> bool {
-        for pos in self.positions.iter() {
-            for square in squares.iter() {
-                if (pos.1 + self.translate.1 + 1.0) >= square.row &&
-                   (pos.1 + self.translate.1 + 1.0) <= square.row + 1.0 &&
-                    pos.0 + self.translate.0 == square.column {
-                    return true;
-                } 
-            }
+    /// Whether the block, moved by `x` columns and `y` rows, stays clear of the walls, the floor and the stack.
+    pub fn fits(&self, board: &Board, x: i32, y: i32) -> bool {
+        self.to_cells().iter().all(|(row, column)| !board.is_blocked(row + y, column + x))
+    }
 
-            if pos.1 + self.translate.1 + 1.0 > BOARD_HEIGHT / SQUARE_SIZE {
-                return true;
-            }
+    /// How many whole rows the block can fall before it lands on the stack or the floor.
+    pub fn drop_distance(&self, board: &Board) -> i32 {
+        let mut distance = 0;
+        while self.fits(board, 0, distance + 1) {
+            distance += 1;
         }
 
-        return false;
+        distance
     }
 
-    pub fn will_collide(&self, squares: &Vec<Square>, movement: f32) -> bool {
-        for pos in self.positions.iter() {
-            let square_column = (pos.0 + self.translate.0 + movement).round();
-            let square_row = (pos.1 + self.translate.1).round();
-            if let Some(_) = squares.iter().find(|s| s.column == square_column && s.row == square_row) {
-                return true;
-            }
+    fn should_stop(&self, board: &Board) -> bool {
+        !self.fits(board, 0, 1)
+    }
 
-            if pos.0 + self.translate.0 + movement + 1.0 > BOARD_WIDTH / SQUARE_SIZE ||
-               pos.0 + self.translate.0 + movement < 0.0 {
-                return true;
-            }
+    pub fn will_collide(&self, board: &Board, movement: i32) -> bool {
+        if !self.fits(board, movement, 0) {
+            return true;
         }
 
-        return self.should_stop(squares);
+        self.should_stop(board)
     }
 }
\ No newline at end of file
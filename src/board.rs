@@ -0,0 +1,147 @@
+use crate::block::*;
+
+use ggez::graphics::Color;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Cell {
+    pub color: Color,
+    pub block_type: BlockType
+}
+
+/// The settled stack, stored as a `width` x `height` matrix of cells.
+/// Row 0 is the top of the playfield; the `BUFFER_ROWS` hidden rows above it (rows -1 and up)
+/// keep the squares of a block that locks partly above the playfield.
+#[derive(Clone)]
+pub struct Board {
+    width: usize,
+    height: usize,
+    cells: Vec<Option<Cell>>,
+    row_counts: Vec<usize>
+}
+
+impl Board {
+    /// As many rows as a kick can lift a block above the top of the playfield.
+    pub const BUFFER_ROWS: usize = 2;
+
+    pub fn new(width: usize, height: usize) -> Self {
+        Board {
+            width,
+            height,
+            cells: vec![None; width * (height + Board::BUFFER_ROWS)],
+            row_counts: vec![0; height + Board::BUFFER_ROWS]
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Whether the cell is on the visible playfield.
+    pub fn is_inside(&self, row: i32, column: i32) -> bool {
+        row >= 0 && column >= 0 && (row as usize) < self.height && (column as usize) < self.width
+    }
+
+    /// Whether the cell is on the playfield or in the hidden rows above it.
+    fn is_stored(&self, row: i32, column: i32) -> bool {
+        row >= -(Board::BUFFER_ROWS as i32) && column >= 0 && row < self.height as i32 && (column as usize) < self.width
+    }
+
+    pub fn get(&self, row: i32, column: i32) -> Option<&Cell> {
+        if !self.is_stored(row, column) {
+            return None;
+        }
+
+        self.cells[self.index(row, column)].as_ref()
+    }
+
+    pub fn is_occupied(&self, row: i32, column: i32) -> bool {
+        self.get(row, column).is_some()
+    }
+
+    /// A cell is blocked when it is settled, left/right of the walls, under the floor or above the
+    /// hidden rows. The hidden rows are open so pieces can spawn and be kicked partially above the playfield.
+    pub fn is_blocked(&self, row: i32, column: i32) -> bool {
+        !self.is_stored(row, column) || self.is_occupied(row, column)
+    }
+
+    pub fn set(&mut self, row: i32, column: i32, cell: Cell) {
+        if !self.is_stored(row, column) {
+            return;
+        }
+
+        let index = self.index(row, column);
+        if self.cells[index].is_none() {
+            self.row_counts[index / self.width] += 1;
+        }
+
+        self.cells[index] = Some(cell);
+    }
+
+    pub fn clear(&mut self, row: i32, column: i32) -> Option<Cell> {
+        if !self.is_stored(row, column) {
+            return None;
+        }
+
+        let index = self.index(row, column);
+        let cell = self.cells[index].take();
+        if cell.is_some() {
+            self.row_counts[index / self.width] -= 1;
+        }
+
+        cell
+    }
+
+    pub fn row_count(&self, row: usize) -> usize {
+        self.row_counts[row + Board::BUFFER_ROWS]
+    }
+
+    pub fn is_row_full(&self, row: usize) -> bool {
+        self.row_count(row) == self.width
+    }
+
+    /// Removes `row` and shifts every row above it, the hidden ones included, one row down.
+    pub fn remove_row(&mut self, row: usize) {
+        let end = self.index(row as i32 + 1, 0);
+
+        self.cells[0..end].rotate_right(self.width);
+        for cell in self.cells[0..self.width].iter_mut() {
+            *cell = None;
+        }
+
+        self.row_counts[0..row + Board::BUFFER_ROWS + 1].rotate_right(1);
+        self.row_counts[0] = 0;
+    }
+
+    /// Removes every full row and returns how many were removed.
+    pub fn clear_full_rows(&mut self) -> i32 {
+        let mut lines_count = 0;
+        for row in 0..self.height {
+            if self.is_row_full(row) {
+                lines_count += 1;
+                self.remove_row(row);
+            }
+        }
+
+        lines_count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.row_counts.iter().all(|count| *count == 0)
+    }
+
+    /// Iterates over the settled cells as `(row, column, cell)`, hidden rows included.
+    pub fn cells(&self) -> impl Iterator<Item = (i32, i32, &Cell)> {
+        let width = self.width;
+        self.cells.iter().enumerate().filter_map(move |(index, cell)| {
+            cell.as_ref().map(|cell| ((index / width) as i32 - Board::BUFFER_ROWS as i32, (index % width) as i32, cell))
+        })
+    }
+
+    fn index(&self, row: i32, column: i32) -> usize {
+        (row + Board::BUFFER_ROWS as i32) as usize * self.width + column as usize
+    }
+}
@@ -1,81 +1,67 @@
-use ggez::audio::{self, SoundSource, Source};
-use ggez::mint::Point2;
-use ggez::graphics;
-use ggez::graphics::{DrawParam};
-use ggez::Context;
-use ggez::GameResult;
+use crate::board::*;
 
-use crate::constants::*;
-use crate::block::*;
+use std::fmt;
+use std::str::FromStr;
 
-pub struct Bomb {
-    pub pos: Point2<f32>,
-    image: graphics::Image,
-    pub sound: Source
+/// The cells a bomb destroys around the cell it explodes in.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BlastPattern {
+    /// The 3x3 square around the bomb
+    Square,
+    /// The bomb's row and column, two cells in every direction
+    Plus,
+    Row,
+    Column,
+    /// Every cell within the given distance of the bomb
+    Radius(i32)
 }
 
-impl Bomb {
-    pub fn new(ctx: &mut Context) -> GameResult<Bomb> {
-        let image = graphics::Image::new(ctx, "/bomb.png")?;
-        let sound = audio::Source::new(ctx, "/bomb.ogg")?;
-        
-        Ok(Bomb {
-            pos: Point2 {
-                x: ENTRY_POINT.0,
-                y: ENTRY_POINT.1
-            },
-            image,
-            sound
-        })
-    }
-
-    pub fn draw(&self, ctx: &mut Context) -> GameResult<()> {
-        graphics::draw(ctx, &self.image, DrawParam {
-            dest: self.pos,
-            .. Default::default()
-        })
-    }
-
-    pub fn translate(&mut self, x: f32, y: f32) {
-        let speed: f32 = y * SQUARE_SIZE;
+impl FromStr for BlastPattern {
+    type Err = String;
 
-        self.pos.x += x;
-        self.pos.y += speed;
-    }
-
-    fn should_stop(&self, squares: &Vec<Square>) -> bool {
-        for square in squares.iter() {
-            if self.pos.y + self.image.height() as f32 >= (square.row + 1.0) * SQUARE_SIZE &&
-               self.pos.y <= square.row * SQUARE_SIZE &&
-               self.pos.x == (square.column + 1.0) * SQUARE_SIZE {
-               
-                return true;
-            } 
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "square" => Ok(BlastPattern::Square),
+            "plus" => Ok(BlastPattern::Plus),
+            "row" => Ok(BlastPattern::Row),
+            "column" => Ok(BlastPattern::Column),
+            _ => match value.strip_prefix("radius_").map(|radius| radius.parse::<i32>()) {
+                Some(Ok(radius)) if radius > 0 => Ok(BlastPattern::Radius(radius)),
+                _ => Err(format!("unknown blast pattern {}", value))
+            }
         }
+    }
+}
 
-        if self.pos.y + self.image.height() as f32 > BOARD_HEIGHT + ENTRY_POINT.0 {
-            return true;
+impl fmt::Display for BlastPattern {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BlastPattern::Square => write!(f, "square"),
+            BlastPattern::Plus => write!(f, "plus"),
+            BlastPattern::Row => write!(f, "row"),
+            BlastPattern::Column => write!(f, "column"),
+            BlastPattern::Radius(radius) => write!(f, "radius_{}", radius)
         }
-
-        return false;
     }
+}
 
-    pub fn will_collide(&self, squares: &Vec<Square>, movement: f32, speed: f32) -> bool {
-        let square_column = ((self.pos.x + movement) / SQUARE_SIZE).round() - 1.0;
-        let square_row = ((self.pos.y) / SQUARE_SIZE + speed).round() - 1.0;
-        if let Some(_) = squares.iter().find(|s| s.column == square_column && s.row == square_row) {
-            return true;
-        }
+impl BlastPattern {
+    /// The board cells, as `(row, column)`, hit by a blast at `row` and `column`.
+    pub fn cells(&self, board: &Board, row: i32, column: i32) -> Vec<(i32, i32)> {
+        let width = board.width() as i32;
+        let height = board.height() as i32;
 
-        if self.pos.x + movement > BOARD_WIDTH ||
-           self.pos.x + movement < ENTRY_POINT.1 {
-            return true;
-        }
-    
-        return self.should_stop(squares);
-    }
+        let cells: Vec<(i32, i32)> = match *self {
+            BlastPattern::Square => (row - 1..=row + 1).flat_map(|r| (column - 1..=column + 1).map(move |c| (r, c))).collect(),
+            BlastPattern::Plus => (-2..=2).map(|d| (row + d, column)).chain((-2..=2).filter(|d| *d != 0).map(|d| (row, column + d))).collect(),
+            BlastPattern::Row => (0..width).map(|c| (row, c)).collect(),
+            BlastPattern::Column => (0..height).map(|r| (r, column)).collect(),
+            BlastPattern::Radius(radius) => (row - radius..=row + radius)
+                .flat_map(|r| (column - radius..=column + radius).map(move |c| (r, c)))
+                .filter(|(r, c)| (r - row).pow(2) + (c - column).pow(2) <= radius.pow(2))
+                .collect()
+        };
 
-    pub fn explode(&mut self) {
-        let _ = self.sound.play_detached();
+        cells.into_iter().filter(|(r, c)| board.is_inside(*r, *c)).collect()
     }
 }
\ No newline at end of file
@@ -1,42 +1,347 @@
-use ini::*;
+use crate::block::*;
+use crate::bomb::*;
+use crate::randomizer::*;
+use crate::engine::*;
+use crate::gravity::*;
+use crate::items::*;
+use crate::speed::*;
+
+use std::collections::HashMap;
 use std::env;
-use crate::constants::*;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+type IniMap = HashMap<String, HashMap<String, Option<String>>>;
+
+#[derive(Debug, PartialEq)]
+pub enum ConfigError {
+    NotFound(PathBuf),
+    ReadFailed { path: PathBuf, message: String },
+    InvalidValue { key: String, value: String },
+    WriteFailed { path: PathBuf, message: String }
+}
 
-#[derive(Clone)]
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConfigError::NotFound(path) => write!(f, "configuration file {} was not found", path.display()),
+            ConfigError::ReadFailed { path, message } => write!(f, "configuration file {} could not be read: {}", path.display(), message),
+            ConfigError::InvalidValue { key, value } => write!(f, "invalid value '{}' for key '{}'", value, key),
+            ConfigError::WriteFailed { path, message } => write!(f, "configuration file {} could not be written: {}", path.display(), message)
+        }
+    }
+}
+
+impl Error for ConfigError {}
+
+#[derive(Clone, Debug, PartialEq)]
 pub struct Configuration {
     classic_mode: bool,
+    board_width: usize,
+    board_height: usize,
     viewing_area_rows_count: i32,
     default_speed: f32,
-    lines_to_level_up: i32
+    lines_to_level_up: i32,
+    starting_level: i32,
+    speed_curve: SpeedCurve,
+    speed_table: Vec<f32>,
+    hold_enabled: bool,
+    ghost_enabled: bool,
+    piece_set: PieceSet,
+    randomizer: RandomizerKind,
+    preview_count: usize,
+    seed: Option<u64>,
+    soft_drop_factor: f32,
+    das_ms: u32,
+    arr_ms: u32,
+    lock_delay_ms: u32,
+    lock_reset: LockReset,
+    lock_reset_limit: i32,
+    rotation_180: bool,
+    all_spin: bool,
+    blast_patterns: Vec<BlastPattern>,
+    explosion_gravity: ExplosionGravity,
+    fuse_ms: u32,
+    item_chance: f32,
+    item_weights: HashMap<ItemKind, u32>
+}
+
+impl Default for Configuration {
+    fn default() -> Self {
+        Configuration {
+            classic_mode: true,
+            board_width: 10,
+            board_height: 18,
+            viewing_area_rows_count: 10,
+            default_speed: 0.01,
+            lines_to_level_up: 10,
+            starting_level: 1,
+            speed_curve: SpeedCurve::Linear,
+            speed_table: Vec::new(),
+            hold_enabled: true,
+            ghost_enabled: true,
+            piece_set: PieceSet::Classic,
+            randomizer: RandomizerKind::Bag7,
+            preview_count: 3,
+            seed: None,
+            soft_drop_factor: 5.0,
+            das_ms: 170,
+            arr_ms: 50,
+            lock_delay_ms: 500,
+            lock_reset: LockReset::Move,
+            lock_reset_limit: 15,
+            rotation_180: false,
+            all_spin: false,
+            blast_patterns: vec!(BlastPattern::Square),
+            explosion_gravity: ExplosionGravity::None,
+            fuse_ms: 0,
+            item_chance: 0.25,
+            item_weights: ItemKind::all().into_iter().map(|kind| (kind, if kind == ItemKind::Bomb { 4 } else { 1 })).collect()
+        }
+    }
 }
 
 impl Configuration {
-    pub fn new() -> Self {
-        let current_directory = env::current_dir().unwrap();
-        let conf_path = [current_directory.to_str().unwrap(), "src\\conf.ini"].join("\\");
+    const FILE_NAME: &'static str = "conf.ini";
+    pub const MAX_STARTING_LEVEL: i32 = 30;
+    pub const MAX_PREVIEW_COUNT: usize = 6;
+
+    /// Loads the configuration from `path` when given, otherwise from the first file found
+    /// by `find_file`. Without any file the built-in defaults are used.
+    pub fn load(path: Option<&Path>) -> Result<Self, ConfigError> {
+        match path {
+            Some(path) => Configuration::from_file(path),
+            None => match Configuration::find_file() {
+                Some(path) => Configuration::from_file(&path),
+                None => Ok(Configuration::default())
+            }
+        }
+    }
 
-        let map = ini!(&conf_path);
+    /// Looks for `tetris/conf.ini` in `$XDG_CONFIG_HOME` (or `~/.config`), then for `conf.ini`
+    /// and `src/conf.ini` in the working directory.
+    pub fn find_file() -> Option<PathBuf> {
+        let mut candidates: Vec<PathBuf> = Vec::new();
 
-        let classic_mode = map["game"]["classic_mode"].clone().unwrap().parse().unwrap();
-        let viewing_area_rows_count = map["game"]["viewing_area_rows_count"].clone().unwrap().parse().unwrap();
-        let default_speed = map["game"]["default_speed"].clone().unwrap().parse().unwrap();
-        let lines_to_level_up = map["game"]["lines_to_level_up"].clone().unwrap().parse().unwrap();
+        if let Some(user_file) = Configuration::user_file() {
+            candidates.push(user_file);
+        }
 
-        Configuration {
+        if let Ok(current_directory) = env::current_dir() {
+            candidates.push(current_directory.join(Configuration::FILE_NAME));
+            candidates.push(current_directory.join("src").join(Configuration::FILE_NAME));
+        }
+
+        candidates.into_iter().find(|path| path.is_file())
+    }
+
+    /// `tetris/conf.ini` in `$XDG_CONFIG_HOME`, or in `~/.config` when it is not set.
+    pub fn user_file() -> Option<PathBuf> {
+        env::var_os("XDG_CONFIG_HOME").map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+            .map(|config_home| config_home.join("tetris").join(Configuration::FILE_NAME))
+    }
+
+    pub fn from_file(path: &Path) -> Result<Self, ConfigError> {
+        if !path.is_file() {
+            return Err(ConfigError::NotFound(path.to_path_buf()));
+        }
+
+        let conf_path = path.to_string_lossy().to_string();
+        let map = ini::macro_safe_load(&conf_path).map_err(|message| ConfigError::ReadFailed {
+            path: path.to_path_buf(),
+            message
+        })?;
+
+        Configuration::from_map(&map)
+    }
+
+    fn from_map(map: &IniMap) -> Result<Self, ConfigError> {
+        let defaults = Configuration::default();
+        let classic_mode = value(map, "game", "classic_mode", defaults.classic_mode)?;
+
+        let blast_patterns = list_value(map, "bomb", "blast_patterns")?;
+        let mut item_weights = HashMap::new();
+        for kind in ItemKind::all() {
+            item_weights.insert(kind, value(map, "items", &kind.to_string(), defaults.item_weight(kind))?);
+        }
+
+        // Without an explicit piece set the extended pieces come with the viewing-area mode, as they always have.
+        let default_piece_set = if classic_mode { PieceSet::Classic } else { PieceSet::Extended };
+
+        let configuration = Configuration {
             classic_mode,
-            viewing_area_rows_count,
-            default_speed,
-            lines_to_level_up
+            board_width: value(map, "board", "width", defaults.board_width)?,
+            board_height: value(map, "board", "height", defaults.board_height)?,
+            viewing_area_rows_count: value(map, "game", "viewing_area_rows_count", defaults.viewing_area_rows_count)?,
+            default_speed: value(map, "game", "default_speed", defaults.default_speed)?,
+            lines_to_level_up: value(map, "game", "lines_to_level_up", defaults.lines_to_level_up)?,
+            starting_level: value(map, "game", "starting_level", defaults.starting_level)?,
+            speed_curve: value(map, "game", "speed_curve", defaults.speed_curve)?,
+            speed_table: list_value(map, "game", "speed_table")?,
+            hold_enabled: value(map, "game", "hold", defaults.hold_enabled)?,
+            ghost_enabled: value(map, "game", "ghost", defaults.ghost_enabled)?,
+            piece_set: value(map, "game", "piece_set", default_piece_set)?,
+            randomizer: value(map, "game", "randomizer", defaults.randomizer)?,
+            preview_count: value(map, "game", "preview_count", defaults.preview_count)?,
+            seed: optional_value(map, "game", "seed")?,
+            soft_drop_factor: value(map, "controls", "soft_drop_factor", defaults.soft_drop_factor)?,
+            das_ms: value(map, "controls", "das_ms", defaults.das_ms)?,
+            arr_ms: value(map, "controls", "arr_ms", defaults.arr_ms)?,
+            lock_delay_ms: value(map, "game", "lock_delay_ms", defaults.lock_delay_ms)?,
+            lock_reset: value(map, "game", "lock_reset", defaults.lock_reset)?,
+            lock_reset_limit: value(map, "game", "lock_reset_limit", defaults.lock_reset_limit)?,
+            rotation_180: value(map, "controls", "rotation_180", defaults.rotation_180)?,
+            all_spin: value(map, "game", "all_spin", defaults.all_spin)?,
+            blast_patterns: if blast_patterns.is_empty() { defaults.blast_patterns } else { blast_patterns },
+            explosion_gravity: value(map, "bomb", "gravity", defaults.explosion_gravity)?,
+            fuse_ms: value(map, "bomb", "fuse_ms", defaults.fuse_ms)?,
+            item_chance: value(map, "items", "chance", defaults.item_chance)?,
+            item_weights
+        };
+
+        configuration.validate()?;
+        Ok(configuration)
+    }
+
+    /// Writes every setting to `path` as an ini file `from_file` reads back, creating its directory if needed.
+    pub fn save(&self, path: &Path) -> Result<(), ConfigError> {
+        let write_failed = |error: std::io::Error| ConfigError::WriteFailed {
+            path: path.to_path_buf(),
+            message: error.to_string()
+        };
+
+        if let Some(directory) = path.parent() {
+            fs::create_dir_all(directory).map_err(write_failed)?;
+        }
+
+        fs::write(path, self.to_ini()).map_err(write_failed)
+    }
+
+    fn to_ini(&self) -> String {
+        let seed = match self.seed {
+            Some(seed) => format!("seed = {}", seed),
+            None => String::from("; seed = 12345")
+        };
+        let speed_table = self.speed_table.iter().map(|gravity| gravity.to_string()).collect::<Vec<String>>().join(", ");
+
+        let lines = vec!(
+            String::from("[game]"),
+            format!("classic_mode = {}", self.classic_mode),
+            format!("viewing_area_rows_count = {}", self.viewing_area_rows_count),
+            format!("default_speed = {}", self.default_speed),
+            format!("lines_to_level_up = {}", self.lines_to_level_up),
+            format!("starting_level = {}", self.starting_level),
+            format!("speed_curve = {}", self.speed_curve),
+            if speed_table.is_empty() { String::from("; speed_table = 0.02, 0.03, 0.05") } else { format!("speed_table = {}", speed_table) },
+            format!("hold = {}", self.hold_enabled),
+            format!("ghost = {}", self.ghost_enabled),
+            format!("lock_delay_ms = {}", self.lock_delay_ms),
+            format!("lock_reset = {}", self.lock_reset),
+            format!("lock_reset_limit = {}", self.lock_reset_limit),
+            format!("piece_set = {}", self.piece_set),
+            format!("randomizer = {}", self.randomizer),
+            format!("preview_count = {}", self.preview_count),
+            format!("all_spin = {}", self.all_spin),
+            seed,
+            String::new(),
+            String::from("[board]"),
+            format!("width = {}", self.board_width),
+            format!("height = {}", self.board_height),
+            String::new(),
+            String::from("[controls]"),
+            format!("soft_drop_factor = {}", self.soft_drop_factor),
+            format!("das_ms = {}", self.das_ms),
+            format!("arr_ms = {}", self.arr_ms),
+            format!("rotation_180 = {}", self.rotation_180),
+            String::new(),
+            String::from("[bomb]"),
+            format!("blast_patterns = {}", self.blast_patterns.iter().map(|pattern| pattern.to_string()).collect::<Vec<String>>().join(", ")),
+            format!("gravity = {}", self.explosion_gravity),
+            format!("fuse_ms = {}", self.fuse_ms),
+            String::new(),
+            String::from("[items]"),
+            format!("chance = {}", self.item_chance)
+        );
+        let item_weights = ItemKind::all().into_iter().map(|kind| format!("{} = {}", kind, self.item_weight(kind)));
+
+        lines.into_iter().chain(item_weights).collect::<Vec<String>>().join("\n") + "\n"
+    }
+
+    fn validate(&self) -> Result<(), ConfigError> {
+        if self.board_width < 4 {
+            return Err(invalid("board", "width", self.board_width));
+        }
+
+        if self.board_height < 4 {
+            return Err(invalid("board", "height", self.board_height));
+        }
+
+        if self.viewing_area_rows_count < 1 || self.viewing_area_rows_count > self.board_height as i32 {
+            return Err(invalid("game", "viewing_area_rows_count", self.viewing_area_rows_count));
+        }
+
+        if self.preview_count < 1 || self.preview_count > Configuration::MAX_PREVIEW_COUNT {
+            return Err(invalid("game", "preview_count", self.preview_count));
+        }
+
+        if self.default_speed < 0.0 {
+            return Err(invalid("game", "default_speed", self.default_speed));
+        }
+
+        if self.starting_level < 1 || self.starting_level > Configuration::MAX_STARTING_LEVEL {
+            return Err(invalid("game", "starting_level", self.starting_level));
+        }
+
+        if self.speed_curve == SpeedCurve::Custom && self.speed_table.is_empty() {
+            return Err(invalid("game", "speed_table", ""));
+        }
+
+        if self.speed_table.iter().any(|gravity| *gravity < 0.0) {
+            return Err(invalid("game", "speed_table", self.speed_table.iter().map(|gravity| gravity.to_string()).collect::<Vec<String>>().join(", ")));
+        }
+
+        if self.soft_drop_factor < 1.0 {
+            return Err(invalid("controls", "soft_drop_factor", self.soft_drop_factor));
+        }
+
+        if self.lock_reset_limit < 0 {
+            return Err(invalid("game", "lock_reset_limit", self.lock_reset_limit));
+        }
+
+        if self.item_chance < 0.0 || self.item_chance > 1.0 {
+            return Err(invalid("items", "chance", self.item_chance));
         }
+
+        if self.lines_to_level_up < 1 {
+            return Err(invalid("game", "lines_to_level_up", self.lines_to_level_up));
+        }
+
+        Ok(())
     }
 
     pub fn classic_mode(&self) -> bool {
         self.classic_mode
     }
 
+    pub fn set_classic_mode(&mut self, classic_mode: bool) {
+        self.classic_mode = classic_mode;
+    }
+
+    pub fn board_width(&self) -> usize {
+        self.board_width
+    }
+
+    pub fn board_height(&self) -> usize {
+        self.board_height
+    }
+
     pub fn viewing_area_rows_count(&self) -> i32 {
         if self.classic_mode {
-            (BOARD_HEIGHT / SQUARE_SIZE + 1.0) as i32
+            self.board_height as i32 + 1
         }
         else {
             self.viewing_area_rows_count
@@ -50,4 +355,210 @@ impl Configuration {
     pub fn lines_to_level_up(&self) -> i32 {
         self.lines_to_level_up
     }
-}
\ No newline at end of file
+
+    /// The level a new game starts on, from 1 to `MAX_STARTING_LEVEL`.
+    pub fn starting_level(&self) -> i32 {
+        self.starting_level
+    }
+
+    pub fn set_starting_level(&mut self, starting_level: i32) {
+        self.starting_level = starting_level.clamp(1, Configuration::MAX_STARTING_LEVEL);
+    }
+
+    pub fn speed_curve(&self) -> SpeedCurve {
+        self.speed_curve
+    }
+
+    /// The gravity, in rows per frame, of every level for `SpeedCurve::Custom`; levels past the end use the last entry.
+    pub fn speed_table(&self) -> &[f32] {
+        &self.speed_table
+    }
+
+    pub fn hold_enabled(&self) -> bool {
+        self.hold_enabled
+    }
+
+    pub fn set_hold_enabled(&mut self, hold_enabled: bool) {
+        self.hold_enabled = hold_enabled;
+    }
+
+    pub fn ghost_enabled(&self) -> bool {
+        self.ghost_enabled
+    }
+
+    pub fn set_ghost_enabled(&mut self, ghost_enabled: bool) {
+        self.ghost_enabled = ghost_enabled;
+    }
+
+    pub fn piece_set(&self) -> PieceSet {
+        self.piece_set
+    }
+
+    pub fn set_piece_set(&mut self, piece_set: PieceSet) {
+        self.piece_set = piece_set;
+    }
+
+    pub fn randomizer(&self) -> RandomizerKind {
+        self.randomizer
+    }
+
+    pub fn set_randomizer(&mut self, randomizer: RandomizerKind) {
+        self.randomizer = randomizer;
+    }
+
+    pub fn preview_count(&self) -> usize {
+        self.preview_count
+    }
+
+    pub fn set_preview_count(&mut self, preview_count: usize) {
+        self.preview_count = preview_count.clamp(1, Configuration::MAX_PREVIEW_COUNT);
+    }
+
+    /// The seed for the game RNG; `None` picks a random seed for every game.
+    pub fn seed(&self) -> Option<u64> {
+        self.seed
+    }
+
+    pub fn set_seed(&mut self, seed: Option<u64>) {
+        self.seed = seed;
+    }
+
+    /// How many times faster than gravity the block falls while soft dropping.
+    pub fn soft_drop_factor(&self) -> f32 {
+        self.soft_drop_factor
+    }
+
+    pub fn set_soft_drop_factor(&mut self, soft_drop_factor: f32) {
+        self.soft_drop_factor = soft_drop_factor.max(1.0);
+    }
+
+    /// How long a direction key has to be held before the block starts sliding (delayed auto shift).
+    pub fn das_ms(&self) -> u32 {
+        self.das_ms
+    }
+
+    pub fn set_das_ms(&mut self, das_ms: u32) {
+        self.das_ms = das_ms;
+    }
+
+    /// The time between moves while sliding (auto repeat rate); 0 slides straight to the wall.
+    pub fn arr_ms(&self) -> u32 {
+        self.arr_ms
+    }
+
+    pub fn set_arr_ms(&mut self, arr_ms: u32) {
+        self.arr_ms = arr_ms;
+    }
+
+    /// How long a block may rest on the stack before it locks; 0 locks on contact.
+    pub fn lock_delay_ms(&self) -> u32 {
+        self.lock_delay_ms
+    }
+
+    pub fn lock_reset(&self) -> LockReset {
+        self.lock_reset
+    }
+
+    /// How many moves or rotations may restart the lock delay in `LockReset::Move` mode.
+    pub fn lock_reset_limit(&self) -> i32 {
+        self.lock_reset_limit
+    }
+
+    pub fn rotation_180(&self) -> bool {
+        self.rotation_180
+    }
+
+    pub fn set_rotation_180(&mut self, rotation_180: bool) {
+        self.rotation_180 = rotation_180;
+    }
+
+    /// Whether blocks other than T score spins when they are rotated into a spot they cannot move out of.
+    pub fn all_spin(&self) -> bool {
+        self.all_spin
+    }
+
+    pub fn set_all_spin(&mut self, all_spin: bool) {
+        self.all_spin = all_spin;
+    }
+
+    /// The blasts a bomb can have; every bomb picks one of them at random. Never empty.
+    pub fn blast_patterns(&self) -> &[BlastPattern] {
+        &self.blast_patterns
+    }
+
+    pub fn set_blast_patterns(&mut self, blast_patterns: Vec<BlastPattern>) {
+        if !blast_patterns.is_empty() {
+            self.blast_patterns = blast_patterns;
+        }
+    }
+
+    /// How the squares above a crater fall after a bomb explodes.
+    pub fn explosion_gravity(&self) -> ExplosionGravity {
+        self.explosion_gravity
+    }
+
+    pub fn set_explosion_gravity(&mut self, explosion_gravity: ExplosionGravity) {
+        self.explosion_gravity = explosion_gravity;
+    }
+
+    /// How long a bomb falls before it goes off by itself; 0 turns the fuse off.
+    pub fn fuse_ms(&self) -> u32 {
+        self.fuse_ms
+    }
+
+    pub fn set_fuse_ms(&mut self, fuse_ms: u32) {
+        self.fuse_ms = fuse_ms;
+    }
+
+    /// The chance, from 0 to 1, that a piece dealt into the queue is a special item instead of a block.
+    pub fn item_chance(&self) -> f32 {
+        self.item_chance
+    }
+
+    pub fn set_item_chance(&mut self, item_chance: f32) {
+        self.item_chance = item_chance.clamp(0.0, 1.0);
+    }
+
+    /// How often an item of `kind` spawns compared to the others; 0 never spawns it.
+    pub fn item_weight(&self, kind: ItemKind) -> u32 {
+        self.item_weights.get(&kind).copied().unwrap_or(0)
+    }
+
+    pub fn set_item_weight(&mut self, kind: ItemKind, weight: u32) {
+        self.item_weights.insert(kind, weight);
+    }
+}
+
+fn value<T: FromStr>(map: &IniMap, section: &str, key: &str, default: T) -> Result<T, ConfigError> {
+    Ok(optional_value(map, section, key)?.unwrap_or(default))
+}
+
+fn optional_value<T: FromStr>(map: &IniMap, section: &str, key: &str) -> Result<Option<T>, ConfigError> {
+    match map.get(section).and_then(|keys| keys.get(key)).and_then(|value| value.clone()) {
+        Some(value) => value.trim().parse().map(Some).map_err(|_| ConfigError::InvalidValue {
+            key: format!("{}.{}", section, key),
+            value
+        }),
+        None => Ok(None)
+    }
+}
+
+/// Reads a comma separated list; a missing key gives an empty list.
+fn list_value<T: FromStr>(map: &IniMap, section: &str, key: &str) -> Result<Vec<T>, ConfigError> {
+    let list: Option<String> = optional_value(map, section, key)?;
+
+    match list {
+        Some(list) => list.split(',').filter(|item| !item.trim().is_empty()).map(|item| item.trim().parse().map_err(|_| ConfigError::InvalidValue {
+            key: format!("{}.{}", section, key),
+            value: list.clone()
+        })).collect(),
+        None => Ok(Vec::new())
+    }
+}
+
+fn invalid<T: ToString>(section: &str, key: &str, value: T) -> ConfigError {
+    ConfigError::InvalidValue {
+        key: format!("{}.{}", section, key),
+        value: value.to_string()
+    }
+}
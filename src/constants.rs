@@ -1,12 +1,23 @@
 pub const SQUARE_SIZE: f32 = 35.0;
 pub const BORDER_SIZE: f32 = 1.0;
-pub const BOARD_WIDTH: f32 = SQUARE_SIZE * 10.0;
-pub const BOARD_HEIGHT: f32 = SQUARE_SIZE * 18.0;
-pub const WINDOW_WIDTH: f32 = BOARD_WIDTH + 9.0 * SQUARE_SIZE;
-pub const WINDOW_HEIGHT: f32 = BOARD_HEIGHT + 2.0 * SQUARE_SIZE;
+pub const SIDE_PANEL_WIDTH: f32 = SQUARE_SIZE * 9.0;
 pub const ENTRY_POINT: (f32, f32) = (SQUARE_SIZE, SQUARE_SIZE);
 
 pub const SINGLE_LINE_POINTS: i32 = 40;
 pub const DOUBLE_LINE_POINTS: i32 = 100;
 pub const TRIPLE_LINE_POINTS: i32 = 300;
-pub const TETRIS_POINTS: i32 = 1200;
\ No newline at end of file
+pub const TETRIS_POINTS: i32 = 1200;
+// Spin points by the number of lines cleared, replacing the line clear points
+pub const MINI_SPIN_POINTS: [i32; 3] = [100, 200, 400];
+pub const SPIN_POINTS: [i32; 4] = [400, 800, 1200, 1600];
+// Added for every clearing lock after the first in a row
+pub const COMBO_POINTS: i32 = 50;
+// A tetris or spin right after another one is worth half as much again
+pub const BACK_TO_BACK_FACTOR: f32 = 1.5;
+// Added when a clear of one to four lines empties the board
+pub const PERFECT_CLEAR_POINTS: [i32; 4] = [800, 1200, 1800, 2000];
+pub const SOFT_DROP_POINTS: i32 = 1;
+pub const HARD_DROP_POINTS: i32 = 2;
+// How long a slow time pickup lasts and how much it slows the fall down
+pub const SLOW_TIME_SECONDS: f32 = 10.0;
+pub const SLOW_TIME_FACTOR: f32 = 0.5;
\ No newline at end of file
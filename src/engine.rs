@@ -0,0 +1,824 @@
+use crate::constants::*;
+use crate::block::*;
+use crate::board::*;
+use crate::gravity::*;
+use crate::input::*;
+use crate::items::*;
+use crate::configuration::*;
+use crate::rotation::*;
+use crate::randomizer::*;
+use crate::spin::*;
+use crate::scoring::*;
+
+use std::collections::{ HashMap, VecDeque };
+use std::fmt;
+use std::mem;
+use std::str::FromStr;
+use rand::{ Rng, SeedableRng, thread_rng, rngs::StdRng };
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GameEvent {
+    ItemUsed(ItemKind),
+    Spin { block_type: BlockType, spin: Spin, lines: i32 },
+    BackToBack,
+    Combo(i32),
+    PerfectClear
+}
+
+/// An entry of the preview queue: a block, or a special item coming instead of one.
+#[derive(Clone)]
+pub enum Piece {
+    // Boxed as a block is much larger than an item
+    Block(Box<Block>),
+    Item(SpecialItem)
+}
+
+impl Piece {
+    pub fn block(&self) -> Option<&Block> {
+        match self {
+            Piece::Block(block) => Some(block.as_ref()),
+            Piece::Item(_) => None
+        }
+    }
+
+    pub fn item(&self) -> Option<SpecialItem> {
+        match self {
+            Piece::Block(_) => None,
+            Piece::Item(item) => Some(*item)
+        }
+    }
+}
+
+/// Why the game ended: a new block spawned overlapping the stack (`BlockOut`), or a block
+/// locked entirely above the visible playfield (`LockOut`).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TopOut {
+    BlockOut,
+    LockOut
+}
+
+/// What refreshes the lock delay once a block touches the stack: any move or rotation
+/// (`Infinity`), moves and rotations up to `lock_reset_limit` times (`Move`), or only
+/// falling to a new lowest row (`Step`).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LockReset {
+    Infinity,
+    Move,
+    Step
+}
+
+impl FromStr for LockReset {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "infinity" => Ok(LockReset::Infinity),
+            "move" => Ok(LockReset::Move),
+            "step" => Ok(LockReset::Step),
+            _ => Err(format!("unknown lock reset {}", value))
+        }
+    }
+}
+
+impl fmt::Display for LockReset {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let value = match self {
+            LockReset::Infinity => "infinity",
+            LockReset::Move => "move",
+            LockReset::Step => "step"
+        };
+
+        write!(f, "{}", value)
+    }
+}
+
+pub struct Game {
+    current_block: Block,
+    next_pieces: VecDeque<Piece>,
+    randomizer: Box<dyn Randomizer>,
+    seed: u64,
+    rng: StdRng,
+    hold_block: Option<Block>,
+    hold_used: bool,
+    board: Board,
+    viewing_area_start_row: i32,
+    item: Option<FallingItem>,
+    top_out: Option<TopOut>,
+    configuration: Configuration,
+    lines: i32,
+    total_lines: i32,
+    pieces: i32,
+    time_played: f32,
+    score: i32,
+    score_keeper: ScoreKeeper,
+    items_used: HashMap<ItemKind, i32>,
+    squares_destroyed: i32,
+    slow_time: f32,
+    fuse_timer: f32,
+    speed: f32,
+    level: i32,
+    ticks: i32,
+    fall_progress: f32,
+    shift_direction: i32,
+    shift_timer: f32,
+    repeat_timer: f32,
+    lock_timer: f32,
+    lock_resets: i32,
+    lowest_row: i32,
+    last_rotation: Option<(Rotation, Placement)>,
+    events: Vec<GameEvent>
+}
+
+impl Game {
+    const VIEWING_AREA_INTERVAL: i32 = 5;
+
+    pub fn new(configuration: Configuration) -> Game {
+        let seed = configuration.seed().unwrap_or_else(|| thread_rng().gen());
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut randomizer = configuration.randomizer().create(configuration.piece_set());
+
+        let current_block = deal_block(randomizer.as_mut(), &mut rng, &configuration);
+        let mut next_pieces = VecDeque::new();
+        for _ in 0..configuration.preview_count() {
+            next_pieces.push_back(deal_piece(randomizer.as_mut(), &mut rng, &configuration));
+        }
+
+        Game {
+            current_block,
+            next_pieces,
+            randomizer,
+            seed,
+            rng,
+            hold_block: None,
+            hold_used: false,
+            board: Board::new(configuration.board_width(), configuration.board_height()),
+            viewing_area_start_row: 0,
+            item: None,
+            top_out: None,
+            configuration: configuration.clone(),
+            lines: 0,
+            total_lines: 0,
+            pieces: 0,
+            time_played: 0.0,
+            score: 0,
+            score_keeper: ScoreKeeper::new(),
+            items_used: HashMap::new(),
+            squares_destroyed: 0,
+            slow_time: 0.0,
+            fuse_timer: 0.0,
+            speed: configuration.speed_curve().gravity(configuration.starting_level(), &configuration),
+            level: configuration.starting_level(),
+            ticks: 0,
+            fall_progress: 0.0,
+            shift_direction: 0,
+            shift_timer: 0.0,
+            repeat_timer: 0.0,
+            lock_timer: 0.0,
+            lock_resets: 0,
+            lowest_row: 0,
+            last_rotation: None,
+            events: Vec::new()
+        }
+    }
+
+    pub fn current_block(&self) -> &Block {
+        &self.current_block
+    }
+
+    /// The preview queue, the first piece being the one that spawns next.
+    pub fn next_pieces(&self) -> &VecDeque<Piece> {
+        &self.next_pieces
+    }
+
+    /// The current block moved to where a hard drop would put it.
+    pub fn ghost_block(&self) -> Block {
+        let mut ghost_block = self.current_block.clone();
+        ghost_block.translate.1 += ghost_block.drop_distance(&self.board);
+
+        ghost_block
+    }
+
+    pub fn hold_block(&self) -> Option<&Block> {
+        self.hold_block.as_ref()
+    }
+
+    /// Whether the current block may still be swapped into the hold slot.
+    pub fn can_hold(&self) -> bool {
+        self.configuration.hold_enabled() && !self.hold_used && self.item.is_none()
+    }
+
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    /// The special item falling instead of a block, if any.
+    pub fn item(&self) -> Option<&FallingItem> {
+        self.item.as_ref()
+    }
+
+    pub fn viewing_area_start_row(&self) -> i32 {
+        self.viewing_area_start_row
+    }
+
+    pub fn game_over(&self) -> bool {
+        self.top_out.is_some()
+    }
+
+    pub fn top_out(&self) -> Option<TopOut> {
+        self.top_out
+    }
+
+    pub fn configuration(&self) -> &Configuration {
+        &self.configuration
+    }
+
+    /// The seed the game RNG started from; a game with the same seed and inputs plays out the same.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn score(&self) -> i32 {
+        self.score
+    }
+
+    pub fn level(&self) -> i32 {
+        self.level
+    }
+
+    /// How many items of `kind` have gone off.
+    pub fn items_used(&self, kind: ItemKind) -> i32 {
+        self.items_used.get(&kind).copied().unwrap_or(0)
+    }
+
+    /// How many squares special items have destroyed.
+    pub fn squares_destroyed(&self) -> i32 {
+        self.squares_destroyed
+    }
+
+    /// The seconds left before the falling bomb goes off by itself, when the fuse is on.
+    pub fn fuse_left(&self) -> Option<f32> {
+        match self.item.as_ref().map(|item| item.item()) {
+            Some(SpecialItem::Bomb(_)) if self.configuration.fuse_ms() > 0 => {
+                Some((self.configuration.fuse_ms() as f32 / 1000.0 - self.fuse_timer).max(0.0))
+            },
+            _ => None
+        }
+    }
+
+    /// The seconds left of a slow time pickup.
+    pub fn slow_time(&self) -> f32 {
+        self.slow_time
+    }
+
+    /// The lines cleared since the last level up.
+    pub fn lines(&self) -> i32 {
+        self.lines
+    }
+
+    pub fn total_lines(&self) -> i32 {
+        self.total_lines
+    }
+
+    /// How many blocks have been locked.
+    pub fn pieces(&self) -> i32 {
+        self.pieces
+    }
+
+    /// The seconds played, counted in game steps.
+    pub fn time_played(&self) -> f32 {
+        self.time_played
+    }
+
+    /// How far, as a fraction of a row, gravity has carried the falling block or item towards the
+    /// next row. The block itself always sits on a whole row; this is only for smooth drawing.
+    pub fn fall_progress(&self) -> f32 {
+        self.fall_progress
+    }
+
+    /// Returns the events raised since the last call, so the front end can play sounds for them.
+    pub fn drain_events(&mut self) -> Vec<GameEvent> {
+        mem::take(&mut self.events)
+    }
+
+    /// Advances the game by one fixed update of `dt` seconds.
+    pub fn step(&mut self, dt: f32, input: &Input) {
+        if self.game_over() {
+            return;
+        }
+
+        self.time_played += dt;
+
+        self.ticks += 1;
+
+        if input.hold {
+            self.hold_current_block();
+        }
+
+        if self.item.is_some() {
+            if input.hard_drop {
+                self.hard_drop_item();
+                return;
+            }
+
+            if input.detonate || self.burn_fuse(dt) {
+                self.explode_item();
+                return;
+            }
+        }
+        else if input.hard_drop {
+            self.hard_drop();
+            return;
+        }
+
+        // `speed` is G at 60 frames per second
+        let mut gravity = self.speed * dt * 60.0;
+        if input.soft_drop {
+            gravity *= self.configuration.soft_drop_factor();
+        }
+
+        if self.slow_time > 0.0 {
+            self.slow_time = (self.slow_time - dt).max(0.0);
+            gravity *= SLOW_TIME_FACTOR;
+        }
+
+        if self.item.is_some() {
+            self.update_item(gravity);
+        }
+        else if self.current_block.fits(&self.board, 0, 1) {
+            let rows = self.fall_current_block(gravity);
+            if input.soft_drop {
+                self.score += rows * SOFT_DROP_POINTS;
+            }
+
+            self.update_lowest_row();
+        }
+        else {
+            self.fall_progress = 0.0;
+
+            if self.update_lock_delay(dt) {
+                return;
+            }
+        }
+
+        if let (Some(rotation), None) = (input.rotate, &self.item) {
+            if self.rotate_current_block(rotation) {
+                self.reset_lock_delay();
+            }
+        }
+
+        if self.ticks >= Game::VIEWING_AREA_INTERVAL {
+            self.update_viewing_area(input.viewing_area_movement);
+
+            self.ticks = 0;
+        }
+
+        self.update_shift(dt, input.movement as i32, input.tap);
+    }
+
+    /// Handles sideways movement: a new press moves one column at once, and holding the key
+    /// for `das_ms` starts repeating every `arr_ms`, an ARR of 0 sliding straight to the wall.
+    fn update_shift(&mut self, dt: f32, direction: i32, tap: i32) {
+        // A key pressed and released between two steps still moves one column
+        if tap != 0 && tap != direction {
+            self.shift(tap);
+        }
+        // Pressing the held direction again starts the auto shift over
+        else if tap != 0 {
+            self.shift_direction = 0;
+        }
+
+        if direction == 0 {
+            self.shift_direction = 0;
+            return;
+        }
+
+        if direction != self.shift_direction {
+            self.shift_direction = direction;
+            self.shift_timer = 0.0;
+            self.repeat_timer = 0.0;
+            self.shift(direction);
+            return;
+        }
+
+        let das = self.configuration.das_ms() as f32 / 1000.0;
+        let arr = self.configuration.arr_ms() as f32 / 1000.0;
+        let charged = self.shift_timer >= das;
+        self.shift_timer += dt;
+
+        if self.shift_timer < das {
+            return;
+        }
+
+        if arr <= 0.0 {
+            while self.shift(direction) {}
+        }
+        else if !charged {
+            self.repeat_timer = 0.0;
+            self.shift(direction);
+        }
+        else {
+            self.repeat_timer += dt;
+            while self.repeat_timer >= arr {
+                self.repeat_timer -= arr;
+                if !self.shift(direction) {
+                    self.repeat_timer = 0.0;
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Moves the falling item or block one column; returns whether it moved.
+    fn shift(&mut self, direction: i32) -> bool {
+        if let Some(item) = &mut self.item {
+            if item.will_collide(&self.board, direction) {
+                return false;
+            }
+
+            item.translate(direction, 0);
+            return true;
+        }
+
+        if self.move_current_block(direction) {
+            self.reset_lock_delay();
+            return true;
+        }
+
+        false
+    }
+
+    /// Counts the time the block has spent on the ground and locks it once the lock delay
+    /// has run out. Returns whether the block was locked.
+    fn update_lock_delay(&mut self, dt: f32) -> bool {
+        self.lock_timer += dt;
+
+        if self.lock_timer * 1000.0 >= self.configuration.lock_delay_ms() as f32 {
+            self.lock_current_block();
+            return true;
+        }
+
+        false
+    }
+
+    /// Restarts the lock delay after a successful move or rotation on the ground.
+    fn reset_lock_delay(&mut self) {
+        if self.lock_timer <= 0.0 {
+            return;
+        }
+
+        match self.configuration.lock_reset() {
+            LockReset::Infinity => self.lock_timer = 0.0,
+            LockReset::Move => {
+                if self.lock_resets < self.configuration.lock_reset_limit() {
+                    self.lock_timer = 0.0;
+                    self.lock_resets += 1;
+                }
+            },
+            LockReset::Step => () //Only falling lower restarts the delay
+        }
+    }
+
+    fn update_lowest_row(&mut self) {
+        let row = self.current_block.translate.1;
+        if row > self.lowest_row {
+            self.lowest_row = row;
+            self.lock_timer = 0.0;
+            self.lock_resets = 0;
+        }
+    }
+
+    /// Makes `block` the falling block; the game is over when it spawns overlapping the stack.
+    fn set_current_block(&mut self, block: Block) {
+        if !block.fits(&self.board, 0, 0) {
+            self.top_out = Some(TopOut::BlockOut);
+        }
+
+        self.lowest_row = block.translate.1;
+        self.current_block = block;
+        self.fall_progress = 0.0;
+        self.lock_timer = 0.0;
+        self.lock_resets = 0;
+        self.last_rotation = None;
+    }
+
+    /// Moves the current block straight to its landing row and locks it.
+    fn hard_drop(&mut self) {
+        let distance = self.current_block.drop_distance(&self.board);
+        self.current_block.translate.1 += distance;
+        self.score += distance * HARD_DROP_POINTS;
+
+        if distance > 0 {
+            self.last_rotation = None;
+        }
+
+        self.lock_current_block();
+    }
+
+    fn lock_current_block(&mut self) {
+        // A spin only counts when the block was rotated into place, not moved or dropped there afterwards
+        let spin = match self.last_rotation.take() {
+            Some((rotation, placement)) => detect_spin(&self.current_block, &self.board, rotation, placement.kick, self.configuration.all_spin()),
+            None => None
+        };
+
+        // A block locking partly above the playfield keeps those squares in the board's hidden rows
+        let cells = self.current_block.to_cells();
+        if cells.iter().all(|(row, _)| *row < 0) {
+            self.top_out = Some(TopOut::LockOut);
+            return;
+        }
+
+        let cell = Cell {
+            color: self.current_block.color(),
+            block_type: self.current_block.block_type()
+        };
+
+        for (row, column) in cells {
+            self.board.set(row, column, cell);
+        }
+
+        self.pieces += 1;
+
+        self.hold_used = false;
+
+        let lines_count = self.board.clear_full_rows();
+
+        if let Some(spin) = spin {
+            self.events.push(GameEvent::Spin {
+                block_type: self.current_block.block_type(),
+                spin,
+                lines: lines_count
+            });
+        }
+
+        self.update_score(lines_count, spin);
+        self.add_lines(lines_count);
+
+        self.spawn_next_piece();
+    }
+
+    /// Adds `gravity` to the fall progress and moves the block down a whole row for every full
+    /// row collected, stopping on the stack. Returns how many rows the block fell.
+    fn fall_current_block(&mut self, gravity: f32) -> i32 {
+        self.fall_progress += gravity;
+
+        let mut rows = 0;
+        while self.fall_progress >= 1.0 && self.current_block.fits(&self.board, 0, 1) {
+            self.current_block.translate(0, 1);
+            self.fall_progress -= 1.0;
+            rows += 1;
+        }
+
+        if rows > 0 {
+            self.last_rotation = None;
+        }
+
+        if !self.current_block.fits(&self.board, 0, 1) {
+            self.fall_progress = 0.0;
+        }
+
+        rows
+    }
+
+    /// Pops the front of the queue and refills it from the randomizer.
+    fn take_next_piece(&mut self) -> Piece {
+        let piece = deal_piece(self.randomizer.as_mut(), &mut self.rng, &self.configuration);
+        self.next_pieces.push_back(piece);
+
+        self.next_pieces.pop_front().unwrap()
+    }
+
+    /// Brings in the next piece of the queue, either as the current block or as a falling item.
+    fn spawn_next_piece(&mut self) {
+        match self.take_next_piece() {
+            Piece::Block(block) => self.set_current_block(*block),
+            Piece::Item(item) => {
+                self.item = Some(FallingItem::new(item));
+                self.fall_progress = 0.0;
+                self.fuse_timer = 0.0;
+            }
+        }
+    }
+
+    fn hold_current_block(&mut self) {
+        if !self.can_hold() {
+            return;
+        }
+
+        let mut held_block = self.current_block.clone();
+        held_block.reset();
+
+        match self.hold_block.take() {
+            Some(block) => self.set_current_block(block),
+            None => self.spawn_next_piece()
+        }
+
+        self.hold_block = Some(held_block);
+        self.hold_used = true;
+    }
+
+    /// Slides the current block sideways; unlike gravity this is allowed while it rests on the stack.
+    fn move_current_block(&mut self, columns: i32) -> bool {
+        if !self.current_block.fits(&self.board, columns, 0) {
+            return false;
+        }
+
+        self.current_block.translate(columns, 0);
+        self.last_rotation = None;
+        true
+    }
+
+    fn rotate_current_block(&mut self, rotation: Rotation) -> bool {
+        if rotation == Rotation::Half && !self.configuration.rotation_180() {
+            return false;
+        }
+
+        match try_rotate(&self.current_block, rotation, &self.board) {
+            Some(placement) => {
+                self.current_block.place(placement);
+                self.last_rotation = Some((rotation, placement));
+                true
+            },
+            None => false
+        }
+    }
+
+    /// Line clears, spins and their combo, back-to-back and perfect clear bonuses are worth their
+    /// points times the level they were made on.
+    fn update_score(&mut self, lines_count: i32, spin: Option<Spin>) {
+        let mut multiplier = self.level as f32;
+        if !self.configuration.classic_mode() {
+            multiplier *= 2.0 + (self.board.height() as f32 - self.configuration.viewing_area_rows_count() as f32) / 10.0;
+        }
+
+        let lock_score = self.score_keeper.lock(lines_count, spin, lines_count > 0 && self.board.is_empty());
+        if lock_score.back_to_back {
+            self.events.push(GameEvent::BackToBack);
+        }
+
+        if lock_score.combo > 0 {
+            self.events.push(GameEvent::Combo(lock_score.combo));
+        }
+
+        if lock_score.perfect_clear {
+            self.events.push(GameEvent::PerfectClear);
+        }
+
+        self.score += (lock_score.points as f32 * multiplier).round() as i32;
+    }
+
+    /// Counts cleared lines towards the totals and levels up every `lines_to_level_up` lines.
+    fn add_lines(&mut self, lines_count: i32) {
+        self.lines += lines_count;
+        self.total_lines += lines_count;
+
+        if self.lines >= self.configuration.lines_to_level_up() {
+            self.level += 1;
+            self.lines = 0;
+            self.speed = self.configuration.speed_curve().gravity(self.level, &self.configuration);
+        }
+    }
+
+    fn update_item(&mut self, gravity: f32) {
+        self.fall_progress += gravity;
+
+        let item = self.item.as_mut().unwrap();
+        while self.fall_progress >= 1.0 && item.fits(&self.board, 0, 1) {
+            item.translate(0, 1);
+            self.fall_progress -= 1.0;
+        }
+
+        if item.will_collide(&self.board, 0) {
+            self.explode_item();
+        }
+    }
+
+    /// Drops the falling item straight down and sets it off where it lands.
+    fn hard_drop_item(&mut self) {
+        let item = self.item.as_mut().unwrap();
+        let mut distance = 0;
+        while item.fits(&self.board, 0, distance + 1) {
+            distance += 1;
+        }
+
+        item.translate(0, distance);
+        self.score += distance * HARD_DROP_POINTS;
+
+        self.explode_item();
+    }
+
+    /// Counts down the fuse of a falling bomb; returns whether it burnt out.
+    fn burn_fuse(&mut self, dt: f32) -> bool {
+        if self.fuse_left().is_none() {
+            return false;
+        }
+
+        self.fuse_timer += dt;
+        self.fuse_left() == Some(0.0)
+    }
+
+    /// Sets the falling item off where it is, whether it landed, was detonated or its fuse burnt out,
+    /// and brings in the next piece.
+    fn explode_item(&mut self) {
+        self.set_off_item();
+        self.spawn_next_piece();
+    }
+
+    /// Destroys the targets of the falling item (or starts the slow time), lets the squares above
+    /// them fall and scores the chain clears.
+    fn set_off_item(&mut self) {
+        let item = self.item.take().unwrap();
+        self.events.push(GameEvent::ItemUsed(item.item().kind()));
+        *self.items_used.entry(item.item().kind()).or_insert(0) += 1;
+        // The item took the place of a block, so the block after it may be held again
+        self.hold_used = false;
+
+        if item.item() == SpecialItem::SlowTime {
+            self.slow_time = SLOW_TIME_SECONDS;
+            return;
+        }
+
+        let destroyed: Vec<(i32, i32)> = item.targets(&self.board).into_iter()
+            .filter(|(row, column)| self.board.clear(*row, *column).is_some())
+            .collect();
+        self.squares_destroyed += destroyed.len() as i32;
+
+        let gravity = self.configuration.explosion_gravity();
+        if gravity == ExplosionGravity::None {
+            return;
+        }
+
+        gravity.apply(&mut self.board, &destroyed);
+
+        // Rows filled by the falling squares clear as a chain, every link scoring like the next lock of a combo
+        loop {
+            let lines_count = self.board.clear_full_rows();
+            if lines_count == 0 {
+                break;
+            }
+
+            self.update_score(lines_count, None);
+            self.add_lines(lines_count);
+            gravity.settle(&mut self.board);
+        }
+    }
+
+    fn update_viewing_area(&mut self, movement: i32) {
+        if self.viewing_area_start_row + self.configuration.viewing_area_rows_count() + movement > self.board.height() as i32 ||
+            self.viewing_area_start_row + movement < 0 {
+
+            return;
+        }
+
+        self.viewing_area_start_row += movement;
+    }
+}
+
+fn deal_block(randomizer: &mut dyn Randomizer, rng: &mut StdRng, configuration: &Configuration) -> Block {
+    let block_type = randomizer.next(rng);
+
+    Block::new(block_type, configuration.clone(), Block::color_of(block_type))
+}
+
+/// Deals the next piece of the queue: a special item with the configured chance in the viewing-area mode, otherwise a block.
+fn deal_piece(randomizer: &mut dyn Randomizer, rng: &mut StdRng, configuration: &Configuration) -> Piece {
+    if !configuration.classic_mode() && rng.gen::<f32>() < configuration.item_chance() {
+        if let Some(item) = roll_item(rng, configuration) {
+            return Piece::Item(item);
+        }
+    }
+
+    Piece::Block(Box::new(deal_block(randomizer, rng, configuration)))
+}
+
+/// Picks a special item by the spawn weights in the configuration; `None` when every weight is 0.
+fn roll_item(rng: &mut StdRng, configuration: &Configuration) -> Option<SpecialItem> {
+    let weights: Vec<(ItemKind, u32)> = ItemKind::all().into_iter().map(|kind| (kind, configuration.item_weight(kind))).collect();
+    let total: u32 = weights.iter().map(|(_, weight)| weight).sum();
+    if total == 0 {
+        return None;
+    }
+
+    let mut roll = rng.gen_range(0..total);
+    let kind = weights.into_iter().find(|(_, weight)| {
+        if roll < *weight {
+            return true;
+        }
+
+        roll -= weight;
+        false
+    })?.0;
+
+    let item = match kind {
+        ItemKind::Bomb => {
+            let patterns = configuration.blast_patterns();
+            SpecialItem::Bomb(patterns[rng.gen_range(0..patterns.len())])
+        },
+        ItemKind::LineEraser => SpecialItem::LineEraser,
+        ItemKind::ColumnDrill => SpecialItem::ColumnDrill,
+        ItemKind::ColorBomb => SpecialItem::ColorBomb,
+        ItemKind::SlowTime => SpecialItem::SlowTime
+    };
+
+    Some(item)
+}
\ No newline at end of file
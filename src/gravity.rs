@@ -0,0 +1,137 @@
+use crate::board::*;
+
+use std::collections::HashSet;
+use std::fmt;
+use std::str::FromStr;
+
+/// What happens to the squares above a bomb's crater.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ExplosionGravity {
+    /// Squares stay where they are, floating if nothing is left under them
+    None,
+    /// Every column closes the holes the blast made, squares above them moving down
+    Naive,
+    /// Every group of touching squares falls as one chunk until it lands on something
+    Cascade
+}
+
+impl FromStr for ExplosionGravity {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "none" => Ok(ExplosionGravity::None),
+            "naive" => Ok(ExplosionGravity::Naive),
+            "cascade" => Ok(ExplosionGravity::Cascade),
+            _ => Err(format!("unknown explosion gravity {}", value))
+        }
+    }
+}
+
+impl fmt::Display for ExplosionGravity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let value = match self {
+            ExplosionGravity::None => "none",
+            ExplosionGravity::Naive => "naive",
+            ExplosionGravity::Cascade => "cascade"
+        };
+
+        write!(f, "{}", value)
+    }
+}
+
+impl ExplosionGravity {
+    /// Lets the squares fall after the cells in `destroyed`, given as `(row, column)`, were emptied.
+    pub fn apply(&self, board: &mut Board, destroyed: &[(i32, i32)]) {
+        match self {
+            ExplosionGravity::None => (),
+            ExplosionGravity::Naive => {
+                let mut destroyed = destroyed.to_vec();
+                destroyed.sort();
+
+                // Top to bottom, so a hole is closed after the holes above it moved down into it
+                for (row, column) in destroyed {
+                    shift_column_down(board, row, column);
+                }
+            },
+            ExplosionGravity::Cascade => cascade(board)
+        }
+    }
+
+    /// Lets the squares fall after a chain clear removed full rows. Removing the rows already moved
+    /// the rows above them down, which is all the naive gravity does.
+    pub fn settle(&self, board: &mut Board) {
+        if *self == ExplosionGravity::Cascade {
+            cascade(board);
+        }
+    }
+}
+
+/// Moves the squares of `column` above `row` one row down, into `row`.
+fn shift_column_down(board: &mut Board, row: i32, column: i32) {
+    for square_row in (-(Board::BUFFER_ROWS as i32)..row).rev() {
+        if let Some(cell) = board.clear(square_row, column) {
+            board.set(square_row + 1, column, cell);
+        }
+    }
+}
+
+/// Drops every chunk of touching squares until nothing can fall any more. Chunks are dropped
+/// lowest first so that the ones above land on where they ended up.
+fn cascade(board: &mut Board) {
+    loop {
+        let mut chunks = chunks(board);
+        chunks.sort_by_key(|chunk| -chunk.iter().map(|(row, _)| *row).max().unwrap_or(0));
+
+        let mut fell = false;
+        for chunk in chunks {
+            let cells: Vec<(i32, i32, Cell)> = chunk.iter()
+                .filter_map(|(row, column)| board.clear(*row, *column).map(|cell| (*row, *column, cell)))
+                .collect();
+
+            let mut distance = 0;
+            while cells.iter().all(|(row, column, _)| !board.is_blocked(row + distance + 1, *column)) {
+                distance += 1;
+            }
+
+            for (row, column, cell) in cells {
+                board.set(row + distance, column, cell);
+            }
+
+            fell |= distance > 0;
+        }
+
+        if !fell {
+            break;
+        }
+    }
+}
+
+/// The groups of squares connected through their sides.
+fn chunks(board: &Board) -> Vec<Vec<(i32, i32)>> {
+    let mut visited = HashSet::new();
+    let mut chunks = Vec::new();
+
+    for (row, column, _) in board.cells() {
+        if !visited.insert((row, column)) {
+            continue;
+        }
+
+        let mut chunk = Vec::new();
+        let mut pending = vec!((row, column));
+
+        while let Some((square_row, square_column)) = pending.pop() {
+            chunk.push((square_row, square_column));
+
+            for (next_row, next_column) in [(square_row - 1, square_column), (square_row + 1, square_column), (square_row, square_column - 1), (square_row, square_column + 1)] {
+                if board.is_occupied(next_row, next_column) && visited.insert((next_row, next_column)) {
+                    pending.push((next_row, next_column));
+                }
+            }
+        }
+
+        chunks.push(chunk);
+    }
+
+    chunks
+}
\ No newline at end of file
@@ -0,0 +1,204 @@
+use crate::configuration::*;
+
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
+
+/// Which table a score goes to. Viewing-area games are scored differently for every row count, so each count has its own table.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ScoreMode {
+    Classic,
+    ViewingArea(i32)
+}
+
+impl ScoreMode {
+    pub fn of(configuration: &Configuration) -> Self {
+        if configuration.classic_mode() {
+            ScoreMode::Classic
+        }
+        else {
+            ScoreMode::ViewingArea(configuration.viewing_area_rows_count())
+        }
+    }
+}
+
+impl FromStr for ScoreMode {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        if value == "classic" {
+            return Ok(ScoreMode::Classic);
+        }
+
+        match value.strip_prefix("viewing_area_").map(|rows| rows.parse::<i32>()) {
+            Some(Ok(rows)) if rows > 0 => Ok(ScoreMode::ViewingArea(rows)),
+            _ => Err(format!("unknown score mode {}", value))
+        }
+    }
+}
+
+impl fmt::Display for ScoreMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ScoreMode::Classic => write!(f, "classic"),
+            ScoreMode::ViewingArea(rows) => write!(f, "viewing_area_{}", rows)
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct HighScore {
+    name: String,
+    score: i32,
+    level: i32,
+    lines: i32
+}
+
+impl HighScore {
+    pub fn new(name: &str, score: i32, level: i32, lines: i32) -> Self {
+        HighScore {
+            name: String::from(name),
+            score,
+            level,
+            lines
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn score(&self) -> i32 {
+        self.score
+    }
+
+    pub fn level(&self) -> i32 {
+        self.level
+    }
+
+    pub fn lines(&self) -> i32 {
+        self.lines
+    }
+
+    /// Names are what the game over screen lets the player type: letters and digits.
+    pub fn is_valid_name(name: &str) -> bool {
+        !name.is_empty() && name.len() <= HighScores::MAX_NAME_LENGTH && name.chars().all(|c| c.is_ascii_alphanumeric())
+    }
+
+    /// Reads a `score, level, lines, name` line of the high-score file.
+    fn parse(line: &str) -> Option<Self> {
+        let values: Vec<&str> = line.split(',').map(|value| value.trim()).collect();
+        if values.len() != 4 || !HighScore::is_valid_name(values[3]) {
+            return None;
+        }
+
+        Some(HighScore::new(values[3], values[0].parse().ok()?, values[1].parse().ok()?, values[2].parse().ok()?))
+    }
+}
+
+/// The best scores of every mode, kept in a versioned text file.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct HighScores {
+    tables: HashMap<ScoreMode, Vec<HighScore>>
+}
+
+impl HighScores {
+    pub const FILE_NAME: &'static str = "high_scores.txt";
+    /// Bumped whenever the file layout changes; files of other versions are ignored.
+    pub const VERSION: u32 = 1;
+    pub const MAX_ENTRIES: usize = 10;
+    pub const MAX_NAME_LENGTH: usize = 8;
+
+    pub fn new() -> Self {
+        HighScores::default()
+    }
+
+    /// Reads the high scores from `path`. A missing, unreadable or corrupt file gives no scores
+    /// instead of an error, so a bad file never keeps the game from starting.
+    pub fn load(path: &Path) -> Self {
+        match fs::read_to_string(path) {
+            Ok(contents) => HighScores::parse(&contents),
+            Err(_) => HighScores::new()
+        }
+    }
+
+    /// Parses the contents of a high-score file, skipping lines that cannot be read.
+    pub fn parse(contents: &str) -> Self {
+        let mut high_scores = HighScores::new();
+        let mut lines = contents.lines().map(|line| line.trim()).filter(|line| !line.is_empty());
+
+        let version = lines.next()
+            .and_then(|line| line.strip_prefix("version"))
+            .and_then(|rest| rest.trim_start().strip_prefix('='))
+            .and_then(|version| version.trim().parse::<u32>().ok());
+        if version != Some(HighScores::VERSION) {
+            return high_scores;
+        }
+
+        let mut mode = None;
+        for line in lines {
+            if line.starts_with('[') && line.ends_with(']') {
+                mode = line[1..line.len() - 1].parse::<ScoreMode>().ok();
+            }
+            else if let (Some(mode), Some(high_score)) = (mode, HighScore::parse(line)) {
+                high_scores.insert(mode, high_score);
+            }
+        }
+
+        high_scores
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(directory) = path.parent() {
+            fs::create_dir_all(directory)?;
+        }
+
+        fs::write(path, self.to_text())
+    }
+
+    fn to_text(&self) -> String {
+        let mut modes: Vec<&ScoreMode> = self.tables.keys().collect();
+        modes.sort_by_key(|mode| mode.to_string());
+
+        let mut lines = vec!(format!("version = {}", HighScores::VERSION));
+        for mode in modes {
+            lines.push(String::new());
+            lines.push(format!("[{}]", mode));
+            for high_score in self.entries(*mode) {
+                lines.push(format!("{}, {}, {}, {}", high_score.score, high_score.level, high_score.lines, high_score.name));
+            }
+        }
+
+        lines.join("\n") + "\n"
+    }
+
+    /// The table of `mode`, best score first.
+    pub fn entries(&self, mode: ScoreMode) -> &[HighScore] {
+        self.tables.get(&mode).map(|table| table.as_slice()).unwrap_or(&[])
+    }
+
+    /// Whether `score` would make it into the table of `mode`.
+    pub fn qualifies(&self, mode: ScoreMode, score: i32) -> bool {
+        let entries = self.entries(mode);
+
+        score > 0 && (entries.len() < HighScores::MAX_ENTRIES || entries.iter().any(|high_score| score > high_score.score))
+    }
+
+    /// Adds `high_score` to the table of `mode` and returns its place, or `None` when it did not make it.
+    /// A score equal to an older one goes below it.
+    pub fn insert(&mut self, mode: ScoreMode, high_score: HighScore) -> Option<usize> {
+        if !self.qualifies(mode, high_score.score) {
+            return None;
+        }
+
+        let table = self.tables.entry(mode).or_default();
+        let place = table.iter().position(|entry| high_score.score > entry.score).unwrap_or(table.len());
+        table.insert(place, high_score);
+        table.truncate(HighScores::MAX_ENTRIES);
+
+        Some(place)
+    }
+}
\ No newline at end of file
@@ -1,8 +1,17 @@
+use crate::rotation::*;
+
 #[derive(Debug, Default)]
 pub struct Input {
     pub movement: f32,
-    pub rotate: bool,
-    pub speed_boost: f32,
+    /// A left (-1) or right (1) press since the last step, kept even when the key is already released
+    pub tap: i32,
+    /// A rotation pressed since the last step; holding the key does not turn the block again
+    pub rotate: Option<Rotation>,
+    pub hold: bool,
+    pub soft_drop: bool,
+    pub hard_drop: bool,
+    /// Sets the falling item off where it is
+    pub detonate: bool,
     pub viewing_area_movement: i32
 }
 
@@ -0,0 +1,156 @@
+use crate::board::*;
+use crate::bomb::*;
+
+use std::fmt;
+use std::str::FromStr;
+
+/// The kinds of special items, without the details of a single item such as a bomb's blast.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ItemKind {
+    Bomb,
+    LineEraser,
+    ColumnDrill,
+    ColorBomb,
+    SlowTime
+}
+
+impl ItemKind {
+    pub fn all() -> Vec<ItemKind> {
+        vec!(ItemKind::Bomb, ItemKind::LineEraser, ItemKind::ColumnDrill, ItemKind::ColorBomb, ItemKind::SlowTime)
+    }
+}
+
+impl FromStr for ItemKind {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "bomb" => Ok(ItemKind::Bomb),
+            "line_eraser" => Ok(ItemKind::LineEraser),
+            "column_drill" => Ok(ItemKind::ColumnDrill),
+            "color_bomb" => Ok(ItemKind::ColorBomb),
+            "slow_time" => Ok(ItemKind::SlowTime),
+            _ => Err(format!("unknown item {}", value))
+        }
+    }
+}
+
+impl fmt::Display for ItemKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let value = match self {
+            ItemKind::Bomb => "bomb",
+            ItemKind::LineEraser => "line_eraser",
+            ItemKind::ColumnDrill => "column_drill",
+            ItemKind::ColorBomb => "color_bomb",
+            ItemKind::SlowTime => "slow_time"
+        };
+
+        write!(f, "{}", value)
+    }
+}
+
+/// What a special item does once it lands.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SpecialItem {
+    /// Destroys the squares in its blast pattern
+    Bomb(BlastPattern),
+    /// Erases the row it lands on
+    LineEraser,
+    /// Destroys the squares under it, all the way to the floor
+    ColumnDrill,
+    /// Removes every square of the same color as the one it lands on
+    ColorBomb,
+    /// Slows the fall down for a while instead of destroying anything
+    SlowTime
+}
+
+impl SpecialItem {
+    pub fn kind(&self) -> ItemKind {
+        match self {
+            SpecialItem::Bomb(_) => ItemKind::Bomb,
+            SpecialItem::LineEraser => ItemKind::LineEraser,
+            SpecialItem::ColumnDrill => ItemKind::ColumnDrill,
+            SpecialItem::ColorBomb => ItemKind::ColorBomb,
+            SpecialItem::SlowTime => ItemKind::SlowTime
+        }
+    }
+
+    /// The board cells, as `(row, column)`, the item destroys when it goes off at `row` and `column`.
+    pub fn targets(&self, board: &Board, row: i32, column: i32) -> Vec<(i32, i32)> {
+        // The row the item rests on, or its own row when it lies on the floor
+        let landing_row = if board.is_inside(row + 1, column) { row + 1 } else { row };
+
+        match self {
+            SpecialItem::Bomb(pattern) => pattern.cells(board, row, column),
+            SpecialItem::LineEraser => BlastPattern::Row.cells(board, landing_row, column),
+            SpecialItem::ColumnDrill => (row..board.height() as i32).map(|r| (r, column)).filter(|(r, c)| board.is_inside(*r, *c)).collect(),
+            SpecialItem::ColorBomb => match board.get(row + 1, column) {
+                Some(target) => {
+                    let color = target.color;
+                    board.cells().filter(|(_, _, cell)| cell.color == color).map(|(r, c, _)| (r, c)).collect()
+                },
+                None => Vec::new()
+            },
+            SpecialItem::SlowTime => Vec::new()
+        }
+    }
+}
+
+/// A special item falling like a one-cell piece.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FallingItem {
+    row: i32,
+    column: i32,
+    item: SpecialItem
+}
+
+impl FallingItem {
+    /// An item at the entry point, in the top left cell.
+    pub fn new(item: SpecialItem) -> FallingItem {
+        FallingItem {
+            row: 0,
+            column: 0,
+            item
+        }
+    }
+
+    pub fn translate(&mut self, x: i32, y: i32) {
+        self.column += x;
+        self.row += y;
+    }
+
+    pub fn column(&self) -> i32 {
+        self.column
+    }
+
+    pub fn row(&self) -> i32 {
+        self.row
+    }
+
+    pub fn item(&self) -> SpecialItem {
+        self.item
+    }
+
+    /// Whether the item, moved by `x` columns and `y` rows, stays clear of the walls, the floor and the stack.
+    pub fn fits(&self, board: &Board, x: i32, y: i32) -> bool {
+        !board.is_blocked(self.row + y, self.column + x)
+    }
+
+    fn should_stop(&self, board: &Board) -> bool {
+        !self.fits(board, 0, 1)
+    }
+
+    /// Whether the item, moved by `movement` columns, would hit the walls or the stack, or already rests on them.
+    pub fn will_collide(&self, board: &Board, movement: i32) -> bool {
+        if !self.fits(board, movement, 0) {
+            return true;
+        }
+
+        self.should_stop(board)
+    }
+
+    /// The cells the item destroys when it goes off where it is.
+    pub fn targets(&self, board: &Board) -> Vec<(i32, i32)> {
+        self.item.targets(board, self.row, self.column)
+    }
+}
\ No newline at end of file
@@ -1,4 +1,15 @@
 pub mod block;
+pub mod board;
 pub mod bomb;
 pub mod constants;
-pub mod configuration;
\ No newline at end of file
+pub mod configuration;
+pub mod engine;
+pub mod gravity;
+pub mod high_scores;
+pub mod input;
+pub mod items;
+pub mod randomizer;
+pub mod rotation;
+pub mod scoring;
+pub mod speed;
+pub mod spin;
\ No newline at end of file
@@ -1,16 +1,18 @@
-mod block;
-mod input;
-mod constants;
 mod assets;
-mod bomb;
-mod configuration;
+mod menu;
 
-use crate::constants::*;
-use crate::block::*;
-use crate::input::*;
 use crate::assets::*;
-use crate::bomb::*;
-use crate::configuration::*;
+use crate::menu::*;
+use tetris::constants::*;
+use tetris::block::*;
+use tetris::board::*;
+use tetris::input::*;
+use tetris::items::*;
+use tetris::configuration::*;
+use tetris::engine::*;
+use tetris::high_scores::*;
+use tetris::rotation::*;
+use tetris::spin::*;
 
 use ggez::event;
 use ggez::audio::{SoundSource};
@@ -20,17 +22,41 @@ use ggez::input as ggez_input;
 use ggez::timer;
 use ggez::{Context, ContextBuilder, GameResult};
 use ggez::event::{EventHandler};
-use ggez::mint::Point2;
-use rand::{ Rng, thread_rng };
+use ggez::mint::{Point2, Vector2};
 use std::env;
 use std::path;
 
 fn main() {
+    let config_path = argument_value("--config").map(path::PathBuf::from);
+    let configuration = match Configuration::load(config_path.as_deref()) {
+        Ok(configuration) => configuration,
+        Err(e) => {
+            println!("Error occured: {}", e);
+            return;
+        }
+    };
+
+    let seed = match argument_value("--seed") {
+        Some(seed) => match seed.parse() {
+            Ok(seed) => Some(seed),
+            Err(_) => {
+                println!("Error occured: invalid seed '{}'", seed);
+                return;
+            }
+        },
+        None => None
+    };
+
+    // The settings screen writes to the file given with --config, otherwise to the user config file,
+    // so it never overwrites the commented conf.ini shipped with the game
+    let config_path = config_path.or_else(Configuration::user_file);
+    let (window_width, window_height) = window_dimensions(&configuration);
+
     let (mut ctx, mut event_loop) = ContextBuilder::new("Tetris", "Vasil")
         .window_setup(ggez::conf::WindowSetup::default().title("Tetris"))
         .window_mode(
             ggez::conf::WindowMode::default()
-            .dimensions(WINDOW_WIDTH, WINDOW_HEIGHT)
+            .dimensions(window_width, window_height)
        )
        .build()
        .unwrap();
@@ -41,7 +67,7 @@ fn main() {
         filesystem::mount(&mut ctx, &path, true);
     }
 
-    let mut tetris_game = Tetris::new(&mut ctx);
+    let mut tetris_game = Tetris::new(&mut ctx, configuration, config_path, seed);
 
     // Run!
     match event::run(&mut ctx, &mut event_loop, &mut tetris_game) {
@@ -50,97 +76,281 @@ fn main() {
     }
 }
 
+/// Returns the value following `name` on the command line, e.g. `--config path/to/conf.ini`.
+fn argument_value(name: &str) -> Option<String> {
+    env::args().skip_while(|argument| argument != name).nth(1)
+}
+
+fn window_dimensions(configuration: &Configuration) -> (f32, f32) {
+    let board_width = configuration.board_width() as f32 * SQUARE_SIZE;
+    let board_height = configuration.board_height() as f32 * SQUARE_SIZE;
+
+    (board_width + SIDE_PANEL_WIDTH, (board_height + 2.0 * SQUARE_SIZE).max(side_panel_height(configuration)))
+}
+
+/// The action text shown for a spin, such as "mini T-spin" over "single".
+fn spin_callout(block_type: BlockType, spin: Spin, lines: i32) -> String {
+    let name = match block_type {
+        BlockType::Plus => String::from("plus"),
+        BlockType::BigZ => String::from("big Z"),
+        _ => format!("{:?}", block_type)
+    };
+
+    let kind = match spin {
+        Spin::Mini => "mini ",
+        Spin::Full => ""
+    };
+
+    let lines = match lines {
+        0 => "",
+        1 => "\nsingle",
+        2 => "\ndouble",
+        3 => "\ntriple",
+        _ => "\nquad"
+    };
+
+    format!("{}{}-spin{}", kind, name, lines)
+}
+
+/// The height needed by the queue, the score and level texts, the item counters, the callout and the hold slot.
+fn side_panel_height(configuration: &Configuration) -> f32 {
+    let queue_height = Tetris::FIRST_PREVIEW_HEIGHT + (configuration.preview_count() - 1) as f32 * Tetris::PREVIEW_HEIGHT;
+    let counters_height = if configuration.classic_mode() { 0.0 } else { 2.0 * SQUARE_SIZE };
+
+    2.0 * SQUARE_SIZE + queue_height + counters_height + 13.0 * SQUARE_SIZE
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Scene {
+    Title,
+    Settings,
+    Playing,
+    GameOver,
+    HighScores
+}
+
 struct Tetris {
-    current_block: Block,
-    next_block: Block,
-    squares: Vec<Square>,
-    input: Input,
-    viewing_area_start_row: i32,
-    bomb: Option<Bomb>,
-    game_over: bool,
+    scene: Scene,
     configuration: Configuration,
-    lines_block_count: Vec<i32>,
-    lines: i32,
-    score: i32,
-    speed: f32,
-    level: i32,
-    ticks: i32
+    config_path: Option<path::PathBuf>,
+    seed: Option<u64>,
+    title_menu: Menu<TitleEntry>,
+    settings_menu: Menu<SettingsEntry>,
+    message: Option<String>,
+    high_scores: HighScores,
+    high_scores_path: path::PathBuf,
+    /// The name being typed on the game over screen, when the score made it into the high scores
+    name_entry: Option<String>,
+    /// The place of the last saved score, marked on the high-score screen
+    new_high_score: Option<usize>,
+    game: Game,
+    input: Input,
+    assets: Assets,
+    callout: Vec<String>,
+    callout_timer: f32,
+    paused: bool
 }
 
 impl Tetris {
-    const ROTATION_INTERVAL: i32 = 5;
-    const MOVE_INTERVAL: i32 = 5;
-
-    pub fn new(ctx: &mut Context) -> Tetris {
+    const FIRST_PREVIEW_HEIGHT: f32 = 3.0 * SQUARE_SIZE;
+    const PREVIEW_HEIGHT: f32 = 1.5 * SQUARE_SIZE;
+    const PREVIEW_SCALE: f32 = 0.5;
+    const CALLOUT_SECONDS: f32 = 2.0;
+    const DESIRED_FPS: u32 = 60;
+
+    /// `seed`, given on the command line, is used for every game started from the title menu.
+    pub fn new(ctx: &mut Context, configuration: Configuration, config_path: Option<path::PathBuf>, seed: Option<u64>) -> Tetris {
         let mut assets = Assets::new(ctx).unwrap();
         assets.theme_song.set_repeat(true);
-        let _ = assets.theme_song.play_detached();
-
-        let configuration = Configuration::new();
+        let _ = assets.theme_song.play();
+        let high_scores_path = filesystem::user_data_dir(ctx).join(HighScores::FILE_NAME);
 
         Tetris
         {
-            current_block: Block::new(rand::random(), configuration.clone()), 
-            next_block: Block::new(rand::random(), configuration.clone()),
-            squares: Vec::new(),
-            input: Input::default(),
-            viewing_area_start_row: 0,
-            bomb: None,
-            game_over: false,
+            scene: Scene::Title,
             configuration: configuration.clone(),
-            lines_block_count: vec![0; (BOARD_HEIGHT / SQUARE_SIZE) as usize],
-            lines: 0,
-            score: 0,
-            speed: configuration.default_speed(),
-            level: 1,
-            ticks: 0
+            config_path,
+            seed,
+            title_menu: Menu::new(TitleEntry::all()),
+            settings_menu: Menu::new(SettingsEntry::all()),
+            message: None,
+            high_scores: HighScores::load(&high_scores_path),
+            high_scores_path,
+            name_entry: None,
+            new_high_score: None,
+            game: Game::new(configuration),
+            input: Input::default(),
+            assets,
+            callout: Vec::new(),
+            callout_timer: 0.0,
+            paused: false
         }
     }
 
-    fn clear_line(&mut self, line: usize) {
-        self.lines += 1;
+    fn board_width(&self) -> f32 {
+        self.game.board().width() as f32 * SQUARE_SIZE
+    }
+
+    fn board_height(&self) -> f32 {
+        self.game.board().height() as f32 * SQUARE_SIZE
+    }
 
-        self.squares.retain(|s| s.row != line as f32);
-        self.lines_block_count[line] = 0;
+    fn cell_component() -> Rect {
+        Rect::new(BORDER_SIZE, BORDER_SIZE, SQUARE_SIZE - (BORDER_SIZE * 2.0), SQUARE_SIZE - (BORDER_SIZE * 2.0))
+    }
 
-        for square in self.squares.iter_mut() {
-            if square.row < line as f32 {
-                square.row += 1.0;
-            }
-        } 
+    fn draw_square(&self, ctx: &mut Context, dest: Point2<f32>, color: Color) -> GameResult<()> {
+        self.draw_scaled_square(ctx, dest, color, 1.0)
+    }
+
+    fn draw_scaled_square(&self, ctx: &mut Context, dest: Point2<f32>, color: Color, scale: f32) -> GameResult<()> {
+        let mut mesh = MeshBuilder::new();
+        mesh.rectangle(DrawMode::fill(), Tetris::cell_component(), color);
 
-        for i in (0..line + 1).rev() {
-            if i == 0 {
-                self.lines_block_count[i] = 0;
+        let mesh = &mesh.build(ctx).unwrap();
+        graphics::draw(ctx, mesh, DrawParam {
+            dest,
+            scale: Vector2 { x: scale, y: scale },
+            .. Default::default()
+        }).unwrap();
+
+        Ok(())
+    }
+
+    fn draw_board(&self, ctx: &mut Context, board: &Board, viewing_area_row: i32) -> GameResult<()> {
+        let viewing_area_rows_count = self.game.configuration().viewing_area_rows_count();
+
+        for (row, column, cell) in board.cells() {
+            if row < viewing_area_row + viewing_area_rows_count &&
+               row >= viewing_area_row {
+
+                self.draw_square(ctx, Point2 {
+                    x: column as f32 * SQUARE_SIZE + ENTRY_POINT.0,
+                    y: row as f32 * SQUARE_SIZE + ENTRY_POINT.1,
+                }, cell.color).unwrap();
             }
-            else {
-                self.lines_block_count[i] = self.lines_block_count[i - 1];
+        }
+
+        Ok(())
+    }
+
+    /// Draws the falling block between its row and the next one, as far as gravity has carried it.
+    fn draw_block(&self, ctx: &mut Context, block: &Block, viewing_area_row: i32) -> GameResult<()> {
+        self.draw_block_with_color(ctx, block, viewing_area_row, block.color(), self.game.fall_progress())
+    }
+
+    fn draw_block_with_color(&self, ctx: &mut Context, block: &Block, viewing_area_row: i32, color: Color, offset: f32) -> GameResult<()> {
+        for (row, column) in block.to_cells() {
+            if row < viewing_area_row + block.configuration().viewing_area_rows_count() &&
+               row >= viewing_area_row {
+
+                self.draw_square(ctx, Point2 {
+                    x: column as f32 * SQUARE_SIZE + ENTRY_POINT.0,
+                    y: (row as f32 + offset) * SQUARE_SIZE + ENTRY_POINT.1,
+                }, color).unwrap();
             }
         }
-    } 
 
-    fn translate_current_block(&mut self, x: f32, y: f32) -> bool {
-        if self.current_block.will_collide(&self.squares, x) {
-            return false;
+        Ok(())
+    }
+
+    /// Draws the landing position of the current block, or a marker on the edge of the viewing
+    /// area it is hidden behind when none of it is inside the viewing area.
+    fn draw_ghost_block(&self, ctx: &mut Context, viewing_area_row: i32) -> GameResult<()> {
+        let ghost_block = self.game.ghost_block();
+        let viewing_area_rows_count = self.game.configuration().viewing_area_rows_count();
+        let color = Color::new(ghost_block.color().r, ghost_block.color().g, ghost_block.color().b, 0.25);
+
+        let cells = ghost_block.to_cells();
+        if cells.iter().any(|(row, _)| *row >= viewing_area_row && *row < viewing_area_row + viewing_area_rows_count) {
+            return self.draw_block_with_color(ctx, &ghost_block, viewing_area_row, color, 0.0);
         }
 
-        self.current_block.translate(x, y);
-        true
+        let indicator_height = SQUARE_SIZE / 4.0;
+        let indicator_y = if cells.iter().all(|(row, _)| *row < viewing_area_row) {
+            ENTRY_POINT.1 + viewing_area_row as f32 * SQUARE_SIZE
+        }
+        else {
+            ENTRY_POINT.1 + (viewing_area_row + viewing_area_rows_count) as f32 * SQUARE_SIZE - indicator_height
+        };
+
+        let indicator_color = Color::new(color.r, color.g, color.b, 1.0);
+        for (_, column) in cells {
+            let indicator = Rect::new(ENTRY_POINT.0 + column as f32 * SQUARE_SIZE, indicator_y, SQUARE_SIZE, indicator_height);
+            self.draw_border(ctx, indicator, indicator_color).unwrap();
+        }
+
+        Ok(())
     }
 
-    fn draw_next_block(&self, ctx: &mut Context) -> GameResult<()> {
-        for square in self.next_block.to_squares() {
-            let mut mesh = MeshBuilder::new();
-            mesh.rectangle(DrawMode::fill(), square.component, square.color);
+    fn draw_preview_block(&self, ctx: &mut Context, block: &Block, dest: Point2<f32>, scale: f32) -> GameResult<()> {
+        for (row, column) in block.to_cells() {
+            self.draw_scaled_square(ctx, Point2 {
+                x: dest.x + column as f32 * SQUARE_SIZE * scale,
+                y: dest.y + row as f32 * SQUARE_SIZE * scale,
+            }, block.color(), scale).unwrap();
+        }
 
-            let mesh = &mesh.build(ctx).unwrap();
-            graphics::draw(ctx, mesh, DrawParam {
-                dest: Point2 {
-                    x: (((BOARD_WIDTH + 2.0 * SQUARE_SIZE) / SQUARE_SIZE) + 2.0 + square.column) * SQUARE_SIZE,
-                    y: (2.0 + square.row) * SQUARE_SIZE,
-                },
+        Ok(())
+    }
+
+    /// Draws a queued block, or the sprite of a queued item, at `dest`.
+    fn draw_preview_piece(&self, ctx: &mut Context, piece: &Piece, dest: Point2<f32>, scale: f32) -> GameResult<()> {
+        match piece {
+            Piece::Block(block) => self.draw_preview_block(ctx, block, dest, scale),
+            Piece::Item(item) => graphics::draw(ctx, self.assets.item_image(item.kind()), DrawParam {
+                dest,
+                scale: Vector2 { x: scale, y: scale },
                 .. Default::default()
-            }).unwrap();
+            })
+        }
+    }
+
+    /// Draws the next queue, the score and level, the item counters and the hold slot stacked in the side panel.
+    fn draw_side_panel(&self, ctx: &mut Context) -> GameResult<()> {
+        let text_x = self.board_width() + 2.5 * SQUARE_SIZE;
+        let preview_x = self.board_width() + 4.0 * SQUARE_SIZE;
+        let mut y = 2.0 * SQUARE_SIZE;
+
+        for (index, piece) in self.game.next_pieces().iter().enumerate() {
+            if index == 0 {
+                self.draw_preview_piece(ctx, piece, Point2 { x: preview_x, y }, 1.0).unwrap();
+                y += Tetris::FIRST_PREVIEW_HEIGHT;
+            }
+            else {
+                self.draw_preview_piece(ctx, piece, Point2 { x: preview_x, y }, Tetris::PREVIEW_SCALE).unwrap();
+                y += Tetris::PREVIEW_HEIGHT;
+            }
+        }
+
+        y += SQUARE_SIZE;
+        self.draw_text(ctx, format!("score: {}", self.game.score().to_string()), Point2 { x: text_x, y }).unwrap();
+
+        y += 2.0 * SQUARE_SIZE;
+        self.draw_text(ctx, format!("level: {}", self.game.level().to_string()), Point2 { x: text_x, y }).unwrap();
+
+        if !self.game.configuration().classic_mode() {
+            y += SQUARE_SIZE;
+            self.draw_text(ctx, format!("bombs: {}", self.game.items_used(ItemKind::Bomb)), Point2 { x: text_x, y }).unwrap();
+
+            y += SQUARE_SIZE;
+            self.draw_text(ctx, format!("destroyed: {}", self.game.squares_destroyed()), Point2 { x: text_x, y }).unwrap();
+        }
+
+        if !self.callout.is_empty() {
+            self.draw_text(ctx, self.callout.join("\n"), Point2 { x: text_x, y: y + SQUARE_SIZE }).unwrap();
+        }
+
+        // Room for the callout under the level
+        y += 3.0 * SQUARE_SIZE;
+
+        if self.game.configuration().hold_enabled() {
+            y += 2.0 * SQUARE_SIZE;
+            self.draw_text(ctx, String::from("hold:"), Point2 { x: text_x, y }).unwrap();
+
+            if let Some(hold_block) = self.game.hold_block() {
+                self.draw_preview_block(ctx, hold_block, Point2 { x: preview_x, y: y + 1.5 * SQUARE_SIZE }, 1.0).unwrap();
+            }
         }
 
         Ok(())
@@ -148,17 +358,18 @@ impl Tetris {
 
     fn draw_borders(&self, ctx: &mut Context) -> GameResult<()> {
         let color = graphics::WHITE;
-        let top = Rect::new(0.0, 0.0, WINDOW_WIDTH, ENTRY_POINT.1);
-        let left = Rect::new(0.0, 0.0, ENTRY_POINT.0, 2.0 * SQUARE_SIZE + BOARD_HEIGHT);
-        let bottom = Rect::new(0.0, BOARD_HEIGHT + ENTRY_POINT.1, 2.0 * SQUARE_SIZE + BOARD_WIDTH, ENTRY_POINT.1);
-        let right = Rect::new(BOARD_WIDTH + ENTRY_POINT.0, 0.0, ENTRY_POINT.0, 2.0 * SQUARE_SIZE + BOARD_HEIGHT);
+        let (board_width, board_height) = (self.board_width(), self.board_height());
+        let top = Rect::new(0.0, 0.0, board_width + SIDE_PANEL_WIDTH, ENTRY_POINT.1);
+        let left = Rect::new(0.0, 0.0, ENTRY_POINT.0, 2.0 * SQUARE_SIZE + board_height);
+        let bottom = Rect::new(0.0, board_height + ENTRY_POINT.1, 2.0 * SQUARE_SIZE + board_width, ENTRY_POINT.1);
+        let right = Rect::new(board_width + ENTRY_POINT.0, 0.0, ENTRY_POINT.0, 2.0 * SQUARE_SIZE + board_height);
 
         self.draw_border(ctx, top, color).unwrap();
         self.draw_border(ctx, bottom, color).unwrap();
         self.draw_border(ctx, left, color).unwrap();
         self.draw_border(ctx, right, color)
     }
-    
+
     fn draw_border(&self, ctx: &mut Context, border: Rect, color: Color) -> GameResult<()> {
         let mut mesh = MeshBuilder::new();
         mesh.rectangle(DrawMode::fill(), border, color);
@@ -175,233 +386,462 @@ impl Tetris {
         Ok(())
     }
 
-    fn update_score(&mut self, lines_count: i32) {
-        let mut multiplier = 1.0;
-        if !self.configuration.classic_mode() {
-            multiplier = 2.0 + (BOARD_HEIGHT / SQUARE_SIZE - self.configuration.viewing_area_rows_count() as f32) / 10.0;
+    fn draw_game(&self, ctx: &mut Context) -> GameResult<()> {
+        let viewing_area_start_row = self.game.viewing_area_start_row();
+        let viewing_area_rows_count = self.game.configuration().viewing_area_rows_count();
+
+        self.draw_board(ctx, self.game.board(), viewing_area_start_row).unwrap();
+
+        if !self.game.configuration().classic_mode() {
+            // The borders of the viewing area
+            self.draw_border(ctx, Rect::new(0.0, ENTRY_POINT.0 + viewing_area_start_row as f32 * SQUARE_SIZE - 5.0, 2.0 * SQUARE_SIZE + self.board_width(), 5.0), graphics::WHITE).unwrap();
+            self.draw_border(ctx, Rect::new(0.0, ENTRY_POINT.0 + (viewing_area_start_row + viewing_area_rows_count) as f32 * SQUARE_SIZE, 2.0 * SQUARE_SIZE + self.board_width(), 5.0), graphics::WHITE).unwrap();
         }
-        match lines_count {
-            1 => self.score += (SINGLE_LINE_POINTS as f32 * multiplier).round() as i32,
-            2 => self.score += (DOUBLE_LINE_POINTS as f32 * multiplier).round() as i32,
-            3 => self.score += (TRIPLE_LINE_POINTS as f32 * multiplier).round() as i32,
-            4 => self.score += (TETRIS_POINTS as f32 * multiplier).round() as i32,
-            _ => () //Do nothing
+
+        if let Some(item) = self.game.item() {
+            let row = item.row();
+            if row < viewing_area_start_row + viewing_area_rows_count && row >= viewing_area_start_row {
+                let dest = Point2 {
+                    x: item.column() as f32 * SQUARE_SIZE + ENTRY_POINT.0,
+                    y: (row as f32 + self.game.fall_progress()) * SQUARE_SIZE + ENTRY_POINT.1
+                };
+
+                graphics::draw(ctx, self.assets.item_image(item.item().kind()), DrawParam {
+                    dest,
+                    .. Default::default()
+                }).unwrap();
+
+                if let Some(fuse_left) = self.game.fuse_left() {
+                    self.draw_text(ctx, format!("{:.1}", fuse_left), Point2 { x: dest.x + SQUARE_SIZE, y: dest.y }).unwrap();
+                }
+            }
         }
-    }
+        else {
+            if self.game.configuration().ghost_enabled() {
+                self.draw_ghost_block(ctx, viewing_area_start_row).unwrap();
+            }
 
-    fn draw_text(&self, ctx: &mut Context, text: String, dest: Point2<f32>) -> GameResult<()> {
-        let mut text_fragment = TextFragment::new(text);
-        text_fragment.color = Some(graphics::WHITE);
-        text_fragment.scale = Some(Scale { x: 20.0, y: 24.0 });
-        text_fragment.font = Some(Font::new(ctx, "/tetris_block.ttf")?);
-        let text = Text::new(text_fragment);
+            self.draw_block(ctx, self.game.current_block(), viewing_area_start_row).unwrap();
+        }
 
-        graphics::draw(ctx, &text, DrawParam {
-            dest,
-            .. Default::default()
-        }).unwrap();
+        self.draw_borders(ctx).unwrap();
+        self.draw_side_panel(ctx).unwrap();
 
         Ok(())
-    }   
-    
-    fn update_bomb(&mut self, speed: f32) {
-        let bomb = self.bomb.as_mut().unwrap();
-        bomb.translate(0.0, speed);
-
-        if bomb.will_collide(&self.squares, 0.0, speed) {
-            self.explode_bomb();
+    }
 
-            self.current_block = self.next_block.clone();
-            self.next_block = Block::new(rand::random(), self.configuration.clone());
+    /// Draws the final stats and how to go on.
+    fn draw_game_over(&self, ctx: &mut Context) -> GameResult<()> {
+        let reason = match self.game.top_out() {
+            Some(TopOut::LockOut) => "LOCK OUT",
+            _ => "BLOCK OUT"
+        };
+        let seconds = self.game.time_played() as i32;
+
+        let mut lines = vec!(
+            format!("GAME OVER! ({})", reason),
+            format!("SCORE: {}", self.game.score()),
+            format!("LEVEL: {}", self.game.level()),
+            format!("LINES: {}", self.game.total_lines()),
+            format!("PIECES: {}", self.game.pieces()),
+            format!("TIME: {}:{:02}", seconds / 60, seconds % 60),
+            format!("SEED: {}", self.game.seed())
+        );
+
+        match &self.name_entry {
+            Some(name) => {
+                lines.push(String::from("NEW HIGH SCORE!"));
+                lines.push(format!("NAME: {}_", name));
+                lines.push(String::from("ENTER - SAVE, ESC - SKIP"));
+            },
+            None => lines.push(String::from("R - RESTART, ENTER - MENU"))
         }
+
+        self.draw_lines(ctx, lines, None)
     }
 
-    fn explode_bomb(&mut self) {
-        let bomb = self.bomb.as_mut().unwrap();
-        bomb.explode();
+    fn draw_title(&self, ctx: &mut Context) -> GameResult<()> {
+        let mut lines = vec!(String::from("TETRIS"), String::new());
+        lines.extend(self.title_menu.entries().iter().map(|entry| entry.label(&self.configuration)));
+
+        if let Some(message) = &self.message {
+            lines.push(String::new());
+            lines.push(message.clone());
+        }
+
+        self.draw_lines(ctx, lines, Some(self.title_menu.selected_index() + 2))
+    }
 
-        let row = (bomb.pos.y / SQUARE_SIZE).round() - 1.0;
-        let column = (bomb.pos.x / SQUARE_SIZE).round() - 1.0;
-        
-        self.squares.retain(|s| row - 1.0 > s.row || s.row > row + 1.0 || column - 1.0 > s.column || s.column > column + 1.0);
+    /// Draws the table of the mode picked in the title menu, marking a score that was just saved.
+    fn draw_high_scores(&self, ctx: &mut Context) -> GameResult<()> {
+        let mode = match ScoreMode::of(&self.configuration) {
+            ScoreMode::Classic => String::from("CLASSIC"),
+            ScoreMode::ViewingArea(rows) => format!("VIEWING AREA ({} ROWS)", rows)
+        };
+        let entries = self.high_scores.entries(ScoreMode::of(&self.configuration));
+
+        let mut lines = vec!(format!("HIGH SCORES - {}", mode), String::new());
+        for (place, high_score) in entries.iter().enumerate() {
+            lines.push(format!("{:>2}. {:<8} {:>7}  L{}", place + 1, high_score.name(), high_score.score(), high_score.level()));
+        }
 
-        self.lines_block_count = vec![0; (BOARD_HEIGHT / SQUARE_SIZE) as usize];
-        for square in self.squares.iter() {
-            self.lines_block_count[square.row as usize] += 1;
+        if entries.is_empty() {
+            lines.push(String::from("NO SCORES YET"));
         }
-        
-        self.bomb = None;
+
+        lines.push(String::new());
+        lines.push(String::from("ENTER - MENU"));
+
+        self.draw_lines(ctx, lines, self.new_high_score.map(|place| place + 2))
     }
 
-    fn update_viewing_area(&mut self) {
-        if (self.viewing_area_start_row + self.configuration.viewing_area_rows_count() + self.input.viewing_area_movement) as f32 > BOARD_HEIGHT / SQUARE_SIZE ||
-            self.viewing_area_start_row + self.input.viewing_area_movement < 0 {
-            
-            return;
-        }  
+    fn draw_settings(&self, ctx: &mut Context) -> GameResult<()> {
+        let mut lines = vec!(String::from("SETTINGS"), String::new());
+        lines.extend(self.settings_menu.entries().iter().map(|entry| entry.label(&self.configuration)));
 
-        self.viewing_area_start_row += self.input.viewing_area_movement;
+        self.draw_lines(ctx, lines, Some(self.settings_menu.selected_index() + 2))
     }
-}
 
-impl EventHandler for Tetris {
-    fn update(&mut self, ctx: &mut Context) -> GameResult<()> {
-        if self.game_over {
-            return Ok(());
+    /// The board stays hidden while paused.
+    fn draw_pause(&self, ctx: &mut Context) -> GameResult<()> {
+        self.draw_borders(ctx)?;
+        self.draw_lines(ctx, vec!(String::from("PAUSED"), String::from("P - RESUME, ESC - MENU")), None)
+    }
+
+    /// Draws `lines` one under another in the middle of the window, marking the `selected` one.
+    fn draw_lines(&self, ctx: &mut Context, lines: Vec<String>, selected: Option<usize>) -> GameResult<()> {
+        let screen = graphics::screen_coordinates(ctx);
+        let x = (screen.w - 400.0) / 2.0;
+        let mut y = (screen.h - lines.len() as f32 * SQUARE_SIZE) / 2.0;
+
+        for (index, line) in lines.into_iter().enumerate() {
+            let text = match selected {
+                Some(selected) if selected == index => format!("> {}", line),
+                Some(_) => format!("  {}", line),
+                None => line
+            };
+
+            self.draw_text(ctx, text, Point2 { x, y })?;
+            y += SQUARE_SIZE;
         }
 
-        self.ticks += 1;
-        
-        const DESIRED_FPS: u32 = 60;
+        Ok(())
+    }
+
+    /// Starts a game with the settings picked in the menus, resizing the window for them.
+    fn start_game(&mut self, ctx: &mut Context) -> GameResult<()> {
+        let mut configuration = self.configuration.clone();
+        if self.seed.is_some() {
+            configuration.set_seed(self.seed);
+        }
+
+        let (window_width, window_height) = window_dimensions(&configuration);
+        graphics::set_drawable_size(ctx, window_width, window_height)?;
+        graphics::set_screen_coordinates(ctx, Rect::new(0.0, 0.0, window_width, window_height))?;
+
+        self.game = Game::new(configuration);
+        self.input = Input::default();
+        self.callout.clear();
+        self.name_entry = None;
+        self.message = None;
+        self.scene = Scene::Playing;
+
+        Ok(())
+    }
+
+    fn show_title(&mut self) {
+        self.set_paused(false);
+        self.input = Input::default();
+        self.scene = Scene::Title;
+    }
 
-        while timer::check_update_time(ctx, DESIRED_FPS) {
-            let seconds = 1.0 / (DESIRED_FPS as f32);
+    /// Writes the settings back to the configuration file and returns to the title menu.
+    fn save_settings(&mut self) {
+        self.message = None;
 
-            let speed = (seconds + self.input.speed_boost + self.speed) * 2.0;
-            if let Some(_) = self.bomb {
-                self.update_bomb(speed);
+        if let Some(config_path) = &self.config_path {
+            if let Err(e) = self.configuration.save(config_path) {
+                println!("Error occured: {}", e);
+                self.message = Some(String::from("SETTINGS NOT SAVED"));
             }
-            else if !self.translate_current_block(0.0, speed) {
-                for square in self.current_block.to_squares() {
-                    if square.row == 0.0 {
-                        self.game_over = true;
-                    }
+        }
 
-                    self.lines_block_count[square.row as usize] += 1;
-                    self.squares.push(square);
-                }
+        self.scene = Scene::Title;
+    }
+
+    fn title_key_down(&mut self, ctx: &mut Context, keycode: event::KeyCode) {
+        let entry = self.title_menu.selected();
 
-                let mut lines_count = 0;
-                for i in 0..self.lines_block_count.len() {
-                    let line_block_count = self.lines_block_count[i];
-                    if line_block_count == 10 {
-                        lines_count += 1;
-                        self.clear_line(i);
+        match keycode {
+            event::KeyCode::Up => self.title_menu.move_selection(-1),
+            event::KeyCode::Down => self.title_menu.move_selection(1),
+            event::KeyCode::Left => entry.change(&mut self.configuration, -1),
+            event::KeyCode::Right => entry.change(&mut self.configuration, 1),
+            event::KeyCode::Return => match entry {
+                TitleEntry::Start => {
+                    if let Err(e) = self.start_game(ctx) {
+                        println!("Error occured: {}", e);
                     }
-                }
+                },
+                TitleEntry::HighScores => {
+                    self.new_high_score = None;
+                    self.scene = Scene::HighScores;
+                },
+                TitleEntry::Settings => self.scene = Scene::Settings,
+                TitleEntry::Quit => event::quit(ctx),
+                _ => entry.change(&mut self.configuration, 1)
+            },
+            event::KeyCode::Escape => event::quit(ctx),
+            _ => (), // Do nothing
+        }
+    }
 
-                self.update_score(lines_count);
+    fn settings_key_down(&mut self, keycode: event::KeyCode) {
+        let entry = self.settings_menu.selected();
 
-                if self.lines >= self.configuration.lines_to_level_up() {
-                    self.level += 1;
-                    self.lines = 0;
-                    self.speed = self.configuration.default_speed() * self.level as f32;
-                }
+        match keycode {
+            event::KeyCode::Up => self.settings_menu.move_selection(-1),
+            event::KeyCode::Down => self.settings_menu.move_selection(1),
+            event::KeyCode::Left => entry.change(&mut self.configuration, -1),
+            event::KeyCode::Right => entry.change(&mut self.configuration, 1),
+            event::KeyCode::Return if entry == SettingsEntry::Back => self.save_settings(),
+            event::KeyCode::Return => entry.change(&mut self.configuration, 1),
+            event::KeyCode::Escape => self.save_settings(),
+            _ => (), // Do nothing
+        }
+    }
 
-                let mut rng = thread_rng();
-                if rng.gen_range(0..4) == 1 && !self.configuration.classic_mode() {
-                    self.bomb = Some(Bomb::new(ctx).unwrap());
-                }
-                else {
-                    self.current_block = self.next_block.clone();
-                    self.next_block = Block::new(rand::random(), self.configuration.clone());
-                }
-                return Ok(());
+    fn game_over_key_down(&mut self, ctx: &mut Context, keycode: event::KeyCode) {
+        if let Some(name) = &mut self.name_entry {
+            match keycode {
+                event::KeyCode::Back => { name.pop(); },
+                event::KeyCode::Return => self.save_high_score(),
+                event::KeyCode::Escape => self.name_entry = None,
+                _ => (), // Letters arrive through text_input_event
             }
+
+            return;
         }
 
-        let current_ticks = self.ticks;
-        if current_ticks >= Tetris::ROTATION_INTERVAL {
-            if self.input.rotate {
-                let old_positions = self.current_block.positions.clone();
-                
-                self.current_block.rotate();
-                if self.current_block.will_collide(&self.squares, 0.0) {
-                    self.current_block.positions = old_positions;
-                }
-            }
+        match keycode {
+            event::KeyCode::R => self.restart(),
+            event::KeyCode::Return => self.show_title(),
+            event::KeyCode::Escape => event::quit(ctx),
+            _ => (), // Do nothing
+        }
+    }
+
+    fn high_scores_key_down(&mut self, keycode: event::KeyCode) {
+        match keycode {
+            event::KeyCode::Return | event::KeyCode::Escape => self.show_title(),
+            _ => (), // Do nothing
+        }
+    }
+
+    /// Adds the finished game under the typed name and shows where it placed.
+    fn save_high_score(&mut self) {
+        let name = match self.name_entry.take() {
+            Some(name) if HighScore::is_valid_name(&name) => name,
+            _ => String::from("PLAYER")
+        };
 
-            self.update_viewing_area();
+        let high_score = HighScore::new(&name, self.game.score(), self.game.level(), self.game.total_lines());
+        self.new_high_score = self.high_scores.insert(ScoreMode::of(self.game.configuration()), high_score);
 
-            self.ticks = 0;
+        if let Err(e) = self.high_scores.save(&self.high_scores_path) {
+            println!("Error occured: {}", e);
         }
 
-        if current_ticks >= Tetris::MOVE_INTERVAL {
-            self.translate_current_block(self.input.movement, 0.0);
-            
-            if let Some(bomb) = &mut self.bomb {
-                if !bomb.will_collide(&self.squares, self.input.movement * SQUARE_SIZE, 0.0) {
-                    bomb.translate(self.input.movement * SQUARE_SIZE, 0.0);
-                }
+        self.scene = Scene::HighScores;
+    }
+
+    fn game_key_down(&mut self, keycode: event::KeyCode, repeat: bool) {
+        if self.paused {
+            match keycode {
+                event::KeyCode::P if !repeat => self.set_paused(false),
+                event::KeyCode::Escape => self.show_title(),
+                _ => (), // Do nothing
             }
 
-            self.ticks = 0;
+            return;
+        }
+
+        match keycode {
+            event::KeyCode::C | event::KeyCode::LShift if !repeat => self.input.hold = true,
+            // While an item falls the rotation keys set it off instead
+            event::KeyCode::Space | event::KeyCode::X | event::KeyCode::Z | event::KeyCode::A if self.game.item().is_some() && !repeat => self.input.detonate = true,
+            event::KeyCode::Space | event::KeyCode::X | event::KeyCode::Z | event::KeyCode::A if self.game.item().is_some() => (), // Held keys do not set off the next item
+            event::KeyCode::Space | event::KeyCode::X if !repeat => self.input.rotate = Some(Rotation::Clockwise),
+            event::KeyCode::Z if !repeat => self.input.rotate = Some(Rotation::CounterClockwise),
+            event::KeyCode::A if !repeat => self.input.rotate = Some(Rotation::Half),
+            event::KeyCode::Left if !repeat => {
+                self.input.movement = -1.0;
+                self.input.tap = -1;
+            },
+            event::KeyCode::Right if !repeat => {
+                self.input.movement = 1.0;
+                self.input.tap = 1;
+            },
+            event::KeyCode::Left => self.input.movement = -1.0,
+            event::KeyCode::Right => self.input.movement = 1.0,
+            event::KeyCode::W => self.input.viewing_area_movement = -1,
+            event::KeyCode::S => self.input.viewing_area_movement = 1,
+            event::KeyCode::Down => self.input.soft_drop = true,
+            event::KeyCode::Up if !repeat => self.input.hard_drop = true,
+            event::KeyCode::P if !repeat => self.set_paused(true),
+            event::KeyCode::Escape => self.show_title(),
+            _ => (), // Do nothing
         }
-            
-        Ok(())
     }
 
-    fn draw(&mut self, ctx: &mut Context) -> GameResult<()> {
-        graphics::clear(ctx, graphics::BLACK);
+    /// Starts a new game with the same configuration and a new seed.
+    fn restart(&mut self) {
+        let mut configuration = self.game.configuration().clone();
+        configuration.set_seed(None);
 
-        if self.game_over {
-            self.draw_text(ctx, format!("GAME OVER! SCORE: {}", self.score), Point2 {
-                x: (WINDOW_WIDTH - 400.0) / 2.0,
-                y: (WINDOW_HEIGHT - 50.0) / 2.0,
-            })?;
+        self.game = Game::new(configuration);
+        self.input = Input::default();
+        self.callout.clear();
+        self.name_entry = None;
+        self.scene = Scene::Playing;
+    }
 
-            graphics::present(ctx)?;
-            return Ok(())
+    /// Pauses or resumes the game and the theme song. A finished game cannot be paused.
+    fn set_paused(&mut self, paused: bool) {
+        if self.paused == paused || self.game.game_over() {
+            return;
         }
 
-        for square in self.squares.iter() {
-            if square.row < (self.viewing_area_start_row + self.configuration.viewing_area_rows_count()) as f32 &&
-               square.row >= self.viewing_area_start_row as f32 {
+        self.paused = paused;
+        // Keys released while paused never reach the game
+        self.input = Input::default();
 
-                square.draw(ctx).unwrap();
-            } 
+        if paused {
+            self.assets.theme_song.pause();
         }
+        else {
+            self.assets.theme_song.resume();
+        }
+    }
 
-        if !self.configuration.classic_mode() {
-            // The borders of the viewing area
-            self.draw_border(ctx, Rect::new(0.0, ENTRY_POINT.0 + self.viewing_area_start_row as f32 * SQUARE_SIZE - 5.0, 2.0 * SQUARE_SIZE + BOARD_WIDTH, 5.0), graphics::WHITE).unwrap();
-            self.draw_border(ctx, Rect::new(0.0, ENTRY_POINT.0 + (self.viewing_area_start_row + self.configuration.viewing_area_rows_count()) as f32 * SQUARE_SIZE, 2.0 * SQUARE_SIZE + BOARD_WIDTH, 5.0), graphics::WHITE).unwrap();
+    fn draw_text(&self, ctx: &mut Context, text: String, dest: Point2<f32>) -> GameResult<()> {
+        let mut text_fragment = TextFragment::new(text);
+        text_fragment.color = Some(graphics::WHITE);
+        text_fragment.scale = Some(Scale { x: 20.0, y: 24.0 });
+        text_fragment.font = Some(Font::new(ctx, "/tetris_block.ttf")?);
+        let text = Text::new(text_fragment);
+
+        graphics::draw(ctx, &text, DrawParam {
+            dest,
+            .. Default::default()
+        }).unwrap();
+
+        Ok(())
+    }
+}
+
+impl EventHandler for Tetris {
+    fn update(&mut self, ctx: &mut Context) -> GameResult<()> {
+        if self.scene != Scene::Playing || self.paused {
+            // Skip the frames that pass outside the game so it does not catch up on them
+            while timer::check_update_time(ctx, Tetris::DESIRED_FPS) {}
+
+            return Ok(());
         }
 
-        if let Some(bomb) = &mut self.bomb {
-            let row = (bomb.pos.y / SQUARE_SIZE).round() - 1.0;
-            if row < (self.viewing_area_start_row + self.configuration.viewing_area_rows_count()) as f32 && row > self.viewing_area_start_row as f32 {
-                bomb.draw(ctx).unwrap();
+        while timer::check_update_time(ctx, Tetris::DESIRED_FPS) {
+            let seconds = 1.0 / (Tetris::DESIRED_FPS as f32);
+
+            self.game.step(seconds, &self.input);
+            self.input.tap = 0;
+            self.input.rotate = None;
+            self.input.hold = false;
+            self.input.hard_drop = false;
+            self.input.detonate = false;
+
+            self.callout_timer -= seconds;
+            if self.callout_timer <= 0.0 {
+                self.callout.clear();
             }
         }
-        else {
-            self.current_block.draw(ctx, self.viewing_area_start_row).unwrap();
 
-            // for square in self.current_block.to_squares().iter_mut() {
-            //     if square.row < (self.viewing_area_start_row + self.configuration.viewing_area_rows_count()) as f32 && square.row >= self.viewing_area_start_row as f32 {
-            //         square.draw(ctx).unwrap();
-            //     } 
-            // }
+        let mut callout = Vec::new();
+        for game_event in self.game.drain_events() {
+            match game_event {
+                GameEvent::ItemUsed(kind) => {
+                    let _ = self.assets.item_sound(kind).play_detached();
+                    if kind == ItemKind::SlowTime {
+                        callout.push(String::from("slow time"));
+                    }
+                },
+                GameEvent::Spin { block_type, spin, lines } => callout.push(spin_callout(block_type, spin, lines)),
+                GameEvent::BackToBack => callout.push(String::from("back-to-back")),
+                GameEvent::Combo(combo) => callout.push(format!("combo {}", combo)),
+                GameEvent::PerfectClear => callout.push(String::from("perfect clear"))
+            }
         }
 
-        self.draw_next_block(ctx).unwrap();
-        self.draw_borders(ctx).unwrap();
-        self.draw_text(ctx, format!("score: {}", self.score.to_string()), Point2 { x: 12.5 * SQUARE_SIZE, y: 6.0 * SQUARE_SIZE }).unwrap();
-        self.draw_text(ctx, format!("level: {}", self.level.to_string()), Point2 { x: 12.5 * SQUARE_SIZE, y: 8.0 * SQUARE_SIZE }).unwrap();
+        if !callout.is_empty() {
+            self.callout = callout;
+            self.callout_timer = Tetris::CALLOUT_SECONDS;
+        }
+
+        if self.game.game_over() {
+            self.scene = Scene::GameOver;
+
+            if self.high_scores.qualifies(ScoreMode::of(self.game.configuration()), self.game.score()) {
+                self.name_entry = Some(String::new());
+            }
+        }
+
+        Ok(())
+    }
+
+    fn draw(&mut self, ctx: &mut Context) -> GameResult<()> {
+        graphics::clear(ctx, graphics::BLACK);
+
+        match self.scene {
+            Scene::Title => self.draw_title(ctx)?,
+            Scene::Settings => self.draw_settings(ctx)?,
+            Scene::GameOver => self.draw_game_over(ctx)?,
+            Scene::HighScores => self.draw_high_scores(ctx)?,
+            Scene::Playing if self.paused => self.draw_pause(ctx)?,
+            Scene::Playing => self.draw_game(ctx)?
+        }
 
         graphics::present(ctx)
     }
 
-    fn key_down_event(&mut self, ctx: &mut Context, keycode: event::KeyCode, _keymod: ggez_input::keyboard::KeyMods, _repeat: bool) {
-        match keycode {
-            event::KeyCode::Space => self.input.rotate = true,
-            event::KeyCode::Left => self.input.movement = -1.0,
-            event::KeyCode::Right => self.input.movement = 1.0,
-            event::KeyCode::W => self.input.viewing_area_movement = -1,
-            event::KeyCode::S => self.input.viewing_area_movement = 1,
-            event::KeyCode::Down => self.input.speed_boost = 0.1,
-            event::KeyCode::Escape => event::quit(ctx),
-            _ => (), // Do nothing
+    fn key_down_event(&mut self, ctx: &mut Context, keycode: event::KeyCode, _keymod: ggez_input::keyboard::KeyMods, repeat: bool) {
+        match self.scene {
+            Scene::Title => self.title_key_down(ctx, keycode),
+            Scene::Settings => self.settings_key_down(keycode),
+            Scene::Playing => self.game_key_down(keycode, repeat),
+            Scene::GameOver => self.game_over_key_down(ctx, keycode),
+            Scene::HighScores => self.high_scores_key_down(keycode)
+        }
+    }
+
+    fn text_input_event(&mut self, _ctx: &mut Context, character: char) {
+        if let (Scene::GameOver, Some(name)) = (self.scene, &mut self.name_entry) {
+            if character.is_ascii_alphanumeric() && name.len() < HighScores::MAX_NAME_LENGTH {
+                name.push(character.to_ascii_uppercase());
+            }
         }
     }
 
     fn key_up_event(&mut self, _ctx: &mut Context, keycode: event::KeyCode, _keymod: ggez_input::keyboard::KeyMods) {
         match keycode {
-            event::KeyCode::Space => self.input.rotate = false,
             event::KeyCode::Left | event::KeyCode::Right => self.input.movement = 0.0,
             event::KeyCode::W | event::KeyCode::S => self.input.viewing_area_movement = 0,
-            | event::KeyCode::Down => self.input.speed_boost = 0.0,
+            event::KeyCode::Down => self.input.soft_drop = false,
             _ => (), // Do nothing
         }
     }
+
+    fn focus_event(&mut self, _ctx: &mut Context, gained: bool) {
+        if !gained && self.scene == Scene::Playing {
+            self.set_paused(true);
+        }
+    }
 }
@@ -0,0 +1,146 @@
+use tetris::block::*;
+use tetris::configuration::*;
+use tetris::randomizer::*;
+
+/// A list of entries the player walks through with the arrow keys.
+pub struct Menu<T: Copy> {
+    entries: Vec<T>,
+    selected: usize
+}
+
+impl<T: Copy> Menu<T> {
+    pub fn new(entries: Vec<T>) -> Self {
+        Menu {
+            entries,
+            selected: 0
+        }
+    }
+
+    pub fn entries(&self) -> &[T] {
+        &self.entries
+    }
+
+    pub fn selected_index(&self) -> usize {
+        self.selected
+    }
+
+    pub fn selected(&self) -> T {
+        self.entries[self.selected]
+    }
+
+    /// Moves the selection by `step` entries, wrapping around at both ends.
+    pub fn move_selection(&mut self, step: i32) {
+        let count = self.entries.len() as i32;
+        self.selected = (self.selected as i32 + step).rem_euclid(count) as usize;
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TitleEntry {
+    Mode,
+    StartingLevel,
+    PieceSet,
+    Start,
+    HighScores,
+    Settings,
+    Quit
+}
+
+impl TitleEntry {
+    pub fn all() -> Vec<TitleEntry> {
+        vec!(TitleEntry::Mode, TitleEntry::StartingLevel, TitleEntry::PieceSet, TitleEntry::Start, TitleEntry::HighScores, TitleEntry::Settings, TitleEntry::Quit)
+    }
+
+    pub fn label(&self, configuration: &Configuration) -> String {
+        match self {
+            TitleEntry::Mode => format!("MODE: {}", if configuration.classic_mode() { "CLASSIC" } else { "VIEWING AREA" }),
+            TitleEntry::StartingLevel => format!("LEVEL: {}", configuration.starting_level()),
+            TitleEntry::PieceSet => format!("PIECES: {}", configuration.piece_set().to_string().to_uppercase()),
+            TitleEntry::Start => String::from("START"),
+            TitleEntry::HighScores => String::from("HIGH SCORES"),
+            TitleEntry::Settings => String::from("SETTINGS"),
+            TitleEntry::Quit => String::from("QUIT")
+        }
+    }
+
+    /// Changes the setting behind the entry one step in `direction`, -1 or 1.
+    pub fn change(&self, configuration: &mut Configuration, direction: i32) {
+        match self {
+            TitleEntry::Mode => configuration.set_classic_mode(!configuration.classic_mode()),
+            TitleEntry::StartingLevel => configuration.set_starting_level(configuration.starting_level() + direction),
+            TitleEntry::PieceSet => configuration.set_piece_set(match configuration.piece_set() {
+                PieceSet::Classic => PieceSet::Extended,
+                PieceSet::Extended => PieceSet::Classic
+            }),
+            _ => () // Nothing to change
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SettingsEntry {
+    Ghost,
+    Hold,
+    PreviewCount,
+    Randomizer,
+    SoftDropFactor,
+    Das,
+    Arr,
+    Rotation180,
+    AllSpin,
+    Back
+}
+
+impl SettingsEntry {
+    const RANDOMIZERS: [RandomizerKind; 4] = [RandomizerKind::Uniform, RandomizerKind::Bag7, RandomizerKind::Bag14, RandomizerKind::Nes];
+    const MILLISECONDS_STEP: i32 = 10;
+
+    pub fn all() -> Vec<SettingsEntry> {
+        vec!(SettingsEntry::Ghost, SettingsEntry::Hold, SettingsEntry::PreviewCount, SettingsEntry::Randomizer, SettingsEntry::SoftDropFactor,
+            SettingsEntry::Das, SettingsEntry::Arr, SettingsEntry::Rotation180, SettingsEntry::AllSpin, SettingsEntry::Back)
+    }
+
+    pub fn label(&self, configuration: &Configuration) -> String {
+        match self {
+            SettingsEntry::Ghost => format!("GHOST: {}", on_off(configuration.ghost_enabled())),
+            SettingsEntry::Hold => format!("HOLD: {}", on_off(configuration.hold_enabled())),
+            SettingsEntry::PreviewCount => format!("PREVIEW: {}", configuration.preview_count()),
+            SettingsEntry::Randomizer => format!("RANDOMIZER: {}", configuration.randomizer().to_string().to_uppercase()),
+            SettingsEntry::SoftDropFactor => format!("SOFT DROP: {}X", configuration.soft_drop_factor()),
+            SettingsEntry::Das => format!("DAS: {} MS", configuration.das_ms()),
+            SettingsEntry::Arr => format!("ARR: {} MS", configuration.arr_ms()),
+            SettingsEntry::Rotation180 => format!("180 ROTATION: {}", on_off(configuration.rotation_180())),
+            SettingsEntry::AllSpin => format!("ALL SPIN: {}", on_off(configuration.all_spin())),
+            SettingsEntry::Back => String::from("SAVE AND GO BACK")
+        }
+    }
+
+    /// Changes the setting behind the entry one step in `direction`, -1 or 1.
+    pub fn change(&self, configuration: &mut Configuration, direction: i32) {
+        match self {
+            SettingsEntry::Ghost => configuration.set_ghost_enabled(!configuration.ghost_enabled()),
+            SettingsEntry::Hold => configuration.set_hold_enabled(!configuration.hold_enabled()),
+            SettingsEntry::PreviewCount => configuration.set_preview_count((configuration.preview_count() as i32 + direction).max(1) as usize),
+            SettingsEntry::Randomizer => {
+                let count = SettingsEntry::RANDOMIZERS.len() as i32;
+                let index = SettingsEntry::RANDOMIZERS.iter().position(|kind| *kind == configuration.randomizer()).unwrap_or(0) as i32;
+
+                configuration.set_randomizer(SettingsEntry::RANDOMIZERS[(index + direction).rem_euclid(count) as usize]);
+            },
+            SettingsEntry::SoftDropFactor => configuration.set_soft_drop_factor(configuration.soft_drop_factor() + direction as f32),
+            SettingsEntry::Das => configuration.set_das_ms(step_milliseconds(configuration.das_ms(), direction)),
+            SettingsEntry::Arr => configuration.set_arr_ms(step_milliseconds(configuration.arr_ms(), direction)),
+            SettingsEntry::Rotation180 => configuration.set_rotation_180(!configuration.rotation_180()),
+            SettingsEntry::AllSpin => configuration.set_all_spin(!configuration.all_spin()),
+            SettingsEntry::Back => () // Nothing to change
+        }
+    }
+}
+
+fn on_off(value: bool) -> &'static str {
+    if value { "ON" } else { "OFF" }
+}
+
+fn step_milliseconds(value: u32, direction: i32) -> u32 {
+    (value as i32 + direction * SettingsEntry::MILLISECONDS_STEP).max(0) as u32
+}
\ No newline at end of file
@@ -0,0 +1,141 @@
+use crate::block::*;
+
+use std::fmt;
+use std::str::FromStr;
+use rand::{ Rng, RngCore, seq::SliceRandom };
+
+/// Produces the sequence of pieces that enter the next queue.
+pub trait Randomizer {
+    fn next(&mut self, rng: &mut dyn RngCore) -> BlockType;
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RandomizerKind {
+    Uniform,
+    Bag7,
+    Bag14,
+    Nes
+}
+
+impl FromStr for RandomizerKind {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "uniform" => Ok(RandomizerKind::Uniform),
+            "bag7" => Ok(RandomizerKind::Bag7),
+            "bag14" => Ok(RandomizerKind::Bag14),
+            "nes" => Ok(RandomizerKind::Nes),
+            _ => Err(format!("unknown randomizer {}", value))
+        }
+    }
+}
+
+impl fmt::Display for RandomizerKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let value = match self {
+            RandomizerKind::Uniform => "uniform",
+            RandomizerKind::Bag7 => "bag7",
+            RandomizerKind::Bag14 => "bag14",
+            RandomizerKind::Nes => "nes"
+        };
+
+        write!(f, "{}", value)
+    }
+}
+
+impl RandomizerKind {
+    pub fn create(&self, piece_set: PieceSet) -> Box<dyn Randomizer> {
+        let pieces = piece_set.pieces();
+
+        match self {
+            RandomizerKind::Uniform => Box::new(UniformRandomizer::new(pieces)),
+            RandomizerKind::Bag7 => Box::new(BagRandomizer::new(pieces, 1)),
+            RandomizerKind::Bag14 => Box::new(BagRandomizer::new(pieces, 2)),
+            RandomizerKind::Nes => Box::new(RerollRandomizer::new(pieces))
+        }
+    }
+}
+
+/// Every piece of the set is equally likely on every draw.
+pub struct UniformRandomizer {
+    pieces: Vec<BlockType>
+}
+
+impl UniformRandomizer {
+    pub fn new(pieces: Vec<BlockType>) -> Self {
+        UniformRandomizer {
+            pieces
+        }
+    }
+}
+
+impl Randomizer for UniformRandomizer {
+    fn next(&mut self, rng: &mut dyn RngCore) -> BlockType {
+        self.pieces[rng.gen_range(0..self.pieces.len())]
+    }
+}
+
+/// Deals shuffled bags holding `copies` of every piece of the set, so a 7-bag with the classic
+/// set is `copies == 1` and a 14-bag is `copies == 2`. With the extended set a bag holds all nine pieces.
+pub struct BagRandomizer {
+    pieces: Vec<BlockType>,
+    copies: usize,
+    bag: Vec<BlockType>
+}
+
+impl BagRandomizer {
+    pub fn new(pieces: Vec<BlockType>, copies: usize) -> Self {
+        BagRandomizer {
+            pieces,
+            copies,
+            bag: Vec::new()
+        }
+    }
+}
+
+impl Randomizer for BagRandomizer {
+    fn next(&mut self, rng: &mut dyn RngCore) -> BlockType {
+        if self.bag.is_empty() {
+            for _ in 0..self.copies {
+                self.bag.extend(self.pieces.iter());
+            }
+
+            self.bag.shuffle(rng);
+        }
+
+        self.bag.pop().unwrap()
+    }
+}
+
+/// The NES algorithm: roll one extra "reroll" value and, when it or a repeat of the
+/// previous piece comes up, roll once more without the extra value.
+pub struct RerollRandomizer {
+    pieces: Vec<BlockType>,
+    last: Option<BlockType>
+}
+
+impl RerollRandomizer {
+    pub fn new(pieces: Vec<BlockType>) -> Self {
+        RerollRandomizer {
+            pieces,
+            last: None
+        }
+    }
+}
+
+impl Randomizer for RerollRandomizer {
+    fn next(&mut self, rng: &mut dyn RngCore) -> BlockType {
+        let roll = rng.gen_range(0..=self.pieces.len());
+
+        let block_type = if roll == self.pieces.len() || Some(self.pieces[roll]) == self.last {
+            self.pieces[rng.gen_range(0..self.pieces.len())]
+        }
+        else {
+            self.pieces[roll]
+        };
+
+        self.last = Some(block_type);
+        block_type
+    }
+}
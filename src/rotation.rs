@@ -0,0 +1,154 @@
+use crate::block::*;
+use crate::board::*;
+
+/// The four SRS orientations: spawn (0), right (R), reverse (2) and left (L).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RotationState {
+    Spawn,
+    Right,
+    Reverse,
+    Left
+}
+
+impl RotationState {
+    pub fn index(&self) -> usize {
+        match self {
+            RotationState::Spawn => 0,
+            RotationState::Right => 1,
+            RotationState::Reverse => 2,
+            RotationState::Left => 3
+        }
+    }
+
+    pub fn from_index(index: usize) -> RotationState {
+        match index % 4 {
+            0 => RotationState::Spawn,
+            1 => RotationState::Right,
+            2 => RotationState::Reverse,
+            _ => RotationState::Left
+        }
+    }
+
+    pub fn rotated(&self, rotation: Rotation) -> RotationState {
+        RotationState::from_index(self.index() + rotation.quarter_turns())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Rotation {
+    Clockwise,
+    CounterClockwise,
+    Half
+}
+
+impl Rotation {
+    /// The number of clockwise quarter turns the rotation is equivalent to.
+    pub fn quarter_turns(&self) -> usize {
+        match self {
+            Rotation::Clockwise => 1,
+            Rotation::Half => 2,
+            Rotation::CounterClockwise => 3
+        }
+    }
+}
+
+/// Where a block ends up after a successful rotation. `kick` is the index of the
+/// kick offset that was used, 0 meaning the block rotated in place.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Placement {
+    pub state: RotationState,
+    pub translate: (i32, i32),
+    pub kick: usize
+}
+
+// Kick offsets are (x, y) with y pointing up, as in the SRS guideline tables.
+// Rows are ordered 0->R, R->0, R->2, 2->R, 2->L, L->2, L->0, 0->L.
+const JLSTZ_KICKS: [[(i32, i32); 5]; 8] = [
+    [(0, 0), (-1, 0), (-1, 1), (0, -2), (-1, -2)],
+    [(0, 0), (1, 0), (1, -1), (0, 2), (1, 2)],
+    [(0, 0), (1, 0), (1, -1), (0, 2), (1, 2)],
+    [(0, 0), (-1, 0), (-1, 1), (0, -2), (-1, -2)],
+    [(0, 0), (1, 0), (1, 1), (0, -2), (1, -2)],
+    [(0, 0), (-1, 0), (-1, -1), (0, 2), (-1, 2)],
+    [(0, 0), (-1, 0), (-1, -1), (0, 2), (-1, 2)],
+    [(0, 0), (1, 0), (1, 1), (0, -2), (1, -2)]
+];
+
+const I_KICKS: [[(i32, i32); 5]; 8] = [
+    [(0, 0), (-2, 0), (1, 0), (-2, -1), (1, 2)],
+    [(0, 0), (2, 0), (-1, 0), (2, 1), (-1, -2)],
+    [(0, 0), (-1, 0), (2, 0), (-1, 2), (2, -1)],
+    [(0, 0), (1, 0), (-2, 0), (1, -2), (-2, 1)],
+    [(0, 0), (2, 0), (-1, 0), (2, 1), (-1, -2)],
+    [(0, 0), (-2, 0), (1, 0), (-2, -1), (1, 2)],
+    [(0, 0), (1, 0), (-2, 0), (1, -2), (-2, 1)],
+    [(0, 0), (-1, 0), (2, 0), (-1, 2), (2, -1)]
+];
+
+// Rows are ordered 0->2, R->L, 2->0, L->R.
+const HALF_KICKS: [[(i32, i32); 6]; 4] = [
+    [(0, 0), (0, 1), (1, 1), (-1, 1), (1, 0), (-1, 0)],
+    [(0, 0), (1, 0), (1, 2), (1, 1), (0, 2), (0, 1)],
+    [(0, 0), (0, -1), (-1, -1), (1, -1), (-1, 0), (1, 0)],
+    [(0, 0), (-1, 0), (-1, 2), (-1, 1), (0, 2), (0, 1)]
+];
+
+// The plus looks the same in every orientation, so it never needs to move.
+const PLUS_KICKS: [(i32, i32); 1] = [(0, 0)];
+
+// The big Z is symmetric under a half turn; try the sides first, then one row up or down.
+const BIG_Z_KICKS: [(i32, i32); 5] = [(0, 0), (-1, 0), (1, 0), (0, 1), (0, -1)];
+
+const NO_KICKS: [(i32, i32); 1] = [(0, 0)];
+
+fn quarter_turn_index(from: RotationState, to: RotationState) -> usize {
+    match (from, to) {
+        (RotationState::Spawn, RotationState::Right) => 0,
+        (RotationState::Right, RotationState::Spawn) => 1,
+        (RotationState::Right, RotationState::Reverse) => 2,
+        (RotationState::Reverse, RotationState::Right) => 3,
+        (RotationState::Reverse, RotationState::Left) => 4,
+        (RotationState::Left, RotationState::Reverse) => 5,
+        (RotationState::Left, RotationState::Spawn) => 6,
+        _ => 7
+    }
+}
+
+/// The kick offsets tried, in order, when a `block_type` rotates from `from` by `rotation`.
+pub fn kicks(block_type: BlockType, from: RotationState, rotation: Rotation) -> &'static [(i32, i32)] {
+    let to = from.rotated(rotation);
+
+    match block_type {
+        BlockType::O => &NO_KICKS,
+        BlockType::Plus => &PLUS_KICKS,
+        BlockType::BigZ => &BIG_Z_KICKS,
+        _ if rotation == Rotation::Half => &HALF_KICKS[from.index()],
+        BlockType::I => &I_KICKS[quarter_turn_index(from, to)],
+        _ => &JLSTZ_KICKS[quarter_turn_index(from, to)]
+    }
+}
+
+fn fits(board: &Board, positions: &[(i32, i32)], translate: (i32, i32)) -> bool {
+    positions.iter().all(|pos| !board.is_blocked(pos.1 + translate.1, pos.0 + translate.0))
+}
+
+/// Tries every kick for the rotation in order and returns the first placement that fits
+/// on `board`, or `None` when the block cannot rotate. The block itself is not changed.
+pub fn try_rotate(block: &Block, rotation: Rotation, board: &Board) -> Option<Placement> {
+    let state = block.rotation_state().rotated(rotation);
+    let positions = block.positions_for(state);
+
+    for (index, kick) in kicks(block.block_type(), block.rotation_state(), rotation).iter().enumerate() {
+        let translate = (block.translate.0 + kick.0, block.translate.1 - kick.1);
+
+        if fits(board, &positions, translate) {
+            return Some(Placement {
+                state,
+                translate,
+                kick: index
+            });
+        }
+    }
+
+    None
+}
@@ -0,0 +1,83 @@
+use crate::constants::*;
+use crate::spin::*;
+
+/// The points a lock scored before the level and mode multipliers, and the bonuses that were part of it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LockScore {
+    pub points: i32,
+    pub combo: i32,
+    pub back_to_back: bool,
+    pub perfect_clear: bool
+}
+
+/// Keeps the combo and back-to-back state from one lock to the next.
+#[derive(Clone, Debug, Default)]
+pub struct ScoreKeeper {
+    combo: i32,
+    back_to_back: bool
+}
+
+impl ScoreKeeper {
+    pub fn new() -> Self {
+        ScoreKeeper::default()
+    }
+
+    /// How many clearing locks in a row came before the last one; 0 when the last lock did not continue a combo.
+    pub fn combo(&self) -> i32 {
+        (self.combo - 1).max(0)
+    }
+
+    /// Whether the last line clear was a tetris or a spin, so the next one of those scores a back-to-back bonus.
+    pub fn back_to_back(&self) -> bool {
+        self.back_to_back
+    }
+
+    /// Scores a lock that cleared `lines_count` lines. A lock without lines ends the combo but keeps
+    /// the back-to-back chain, which only an easy clear (no tetris, no spin) breaks.
+    pub fn lock(&mut self, lines_count: i32, spin: Option<Spin>, perfect_clear: bool) -> LockScore {
+        let mut score = LockScore {
+            points: clear_points(lines_count, spin),
+            combo: 0,
+            back_to_back: false,
+            perfect_clear: false
+        };
+
+        if lines_count == 0 {
+            self.combo = 0;
+            return score;
+        }
+
+        let difficult = lines_count >= 4 || spin.is_some();
+        if difficult && self.back_to_back {
+            score.points = (score.points as f32 * BACK_TO_BACK_FACTOR).round() as i32;
+            score.back_to_back = true;
+        }
+        self.back_to_back = difficult;
+
+        self.combo += 1;
+        score.combo = self.combo();
+        score.points += score.combo * COMBO_POINTS;
+
+        if perfect_clear {
+            score.points += PERFECT_CLEAR_POINTS[(lines_count as usize).min(PERFECT_CLEAR_POINTS.len()) - 1];
+            score.perfect_clear = true;
+        }
+
+        score
+    }
+}
+
+/// The points for clearing `lines_count` lines in one lock; a spin scores its own points instead.
+pub fn clear_points(lines_count: i32, spin: Option<Spin>) -> i32 {
+    match spin {
+        Some(Spin::Full) => SPIN_POINTS[(lines_count as usize).min(SPIN_POINTS.len() - 1)],
+        Some(Spin::Mini) => MINI_SPIN_POINTS[(lines_count as usize).min(MINI_SPIN_POINTS.len() - 1)],
+        None => match lines_count {
+            1 => SINGLE_LINE_POINTS,
+            2 => DOUBLE_LINE_POINTS,
+            3 => TRIPLE_LINE_POINTS,
+            4 => TETRIS_POINTS,
+            _ => 0
+        }
+    }
+}
\ No newline at end of file
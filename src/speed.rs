@@ -0,0 +1,78 @@
+use crate::configuration::*;
+
+use std::fmt;
+use std::str::FromStr;
+
+/// How gravity grows with the level. Every curve gives gravity as G, the rows a block falls
+/// per frame at 60 frames per second.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SpeedCurve {
+    Guideline,
+    Nes,
+    Linear,
+    Custom
+}
+
+impl FromStr for SpeedCurve {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "guideline" => Ok(SpeedCurve::Guideline),
+            "nes" => Ok(SpeedCurve::Nes),
+            "linear" => Ok(SpeedCurve::Linear),
+            "custom" => Ok(SpeedCurve::Custom),
+            _ => Err(format!("unknown speed curve {}", value))
+        }
+    }
+}
+
+impl fmt::Display for SpeedCurve {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let value = match self {
+            SpeedCurve::Guideline => "guideline",
+            SpeedCurve::Nes => "nes",
+            SpeedCurve::Linear => "linear",
+            SpeedCurve::Custom => "custom"
+        };
+
+        write!(f, "{}", value)
+    }
+}
+
+// Frames per row on the NTSC NES for levels 0 to 29; level 29 and above fall a row every frame.
+const NES_FRAMES_PER_ROW: [i32; 30] = [
+    48, 43, 38, 33, 28, 23, 18, 13, 8, 6,
+    5, 5, 5, 4, 4, 4, 3, 3, 3, 2,
+    2, 2, 2, 2, 2, 2, 2, 2, 2, 1
+];
+
+// The guideline formula stops speeding up once a block falls the whole board in one frame.
+const MAX_GRAVITY: f32 = 20.0;
+
+// The guideline formula reaches MAX_GRAVITY by this level; past level 115 its base turns negative.
+const GUIDELINE_MAX_LEVEL: i32 = 20;
+
+impl SpeedCurve {
+    /// The gravity at `level`, counting levels from 1.
+    pub fn gravity(&self, level: i32, configuration: &Configuration) -> f32 {
+        let level = level.max(1);
+
+        match self {
+            SpeedCurve::Guideline => {
+                let level = level.min(GUIDELINE_MAX_LEVEL);
+                let seconds_per_row = (0.8 - (level - 1) as f32 * 0.007).powi(level - 1);
+                (1.0 / (seconds_per_row * 60.0)).min(MAX_GRAVITY)
+            },
+            SpeedCurve::Nes => {
+                let index = (level as usize - 1).min(NES_FRAMES_PER_ROW.len() - 1);
+                1.0 / NES_FRAMES_PER_ROW[index] as f32
+            },
+            SpeedCurve::Linear => (1.0 / 60.0 + configuration.default_speed() * level as f32) * 2.0,
+            SpeedCurve::Custom => {
+                let table = configuration.speed_table();
+                table[(level as usize - 1).min(table.len() - 1)]
+            }
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,67 @@
+use crate::block::*;
+use crate::board::*;
+use crate::rotation::*;
+
+/// How a block that was rotated into place is rewarded: a mini spin scores less than a full one.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Spin {
+    Mini,
+    Full
+}
+
+// The cells diagonal to the centre of a T in the spawn state as (x, y), starting with the two it points at.
+const T_CORNERS: [(i32, i32); 4] = [(-1, -1), (1, -1), (1, 1), (-1, 1)];
+
+// The last kick of an SRS quarter turn moves the T two rows, which always makes a full spin.
+// Half turns have no such kick.
+const FULL_SPIN_KICK: usize = 4;
+
+/// Checks a T with the 3-corner rule: at least three of the cells diagonal to its centre have to be
+/// blocked, and the spin is full when both cells the T points at are, or when a quarter turn used its last kick.
+pub fn t_spin(block: &Block, board: &Board, rotation: Rotation, kick: usize) -> Option<Spin> {
+    if block.block_type() != BlockType::T {
+        return None;
+    }
+
+    let center = (block.translate.0 + 1, block.translate.1 + 1);
+    let mut corners = T_CORNERS;
+    for _ in 0..block.rotation_state().index() {
+        for corner in corners.iter_mut() {
+            *corner = (-corner.1, corner.0);
+        }
+    }
+
+    let blocked: Vec<bool> = corners.iter().map(|corner| board.is_blocked(center.1 + corner.1, center.0 + corner.0)).collect();
+    if blocked.iter().filter(|blocked| **blocked).count() < 3 {
+        return None;
+    }
+
+    if (blocked[0] && blocked[1]) || (rotation != Rotation::Half && kick == FULL_SPIN_KICK) {
+        Some(Spin::Full)
+    }
+    else {
+        Some(Spin::Mini)
+    }
+}
+
+/// Whether the block can move neither left, right nor up.
+pub fn is_immobile(block: &Block, board: &Board) -> bool {
+    !block.fits(board, -1, 0) && !block.fits(board, 1, 0) && !block.fits(board, 0, -1)
+}
+
+/// The spin scored by a block that locks right after `rotation` using `kick`. T blocks follow the
+/// 3-corner rule; with `all_spin` every other block that ends up immobile scores a mini spin, except
+/// the O, which turns in place and so can never be twisted into a slot.
+pub fn detect_spin(block: &Block, board: &Board, rotation: Rotation, kick: usize, all_spin: bool) -> Option<Spin> {
+    match block.block_type() {
+        BlockType::T => return t_spin(block, board, rotation, kick),
+        BlockType::O => return None,
+        _ => ()
+    }
+
+    if all_spin && is_immobile(block, board) {
+        return Some(Spin::Mini);
+    }
+
+    None
+}
\ No newline at end of file
@@ -1,62 +1,67 @@
 use tetris::block::*;
+use tetris::board::*;
+use tetris::rotation::*;
 use ggez::graphics;
 use tetris::configuration::*;
-use tetris::constants::*;
 
 #[test]
 fn block_validate_will_stop() {
-    let mut block = Block::new(BlockType::Z, Configuration::new());
-    block.translate(0.0, 15.0);
+    let mut block = Block::new(BlockType::Z, Configuration::default(), graphics::WHITE);
+    block.translate(0, 15);
 
-    let squares = vec!(Square::new(15.0, 0.0, graphics::BLACK));
+    let mut board = Board::new(10, 18);
+    board.set(15, 0, Cell { color: graphics::BLACK, block_type: BlockType::I });
 
-    assert!(block.will_collide(&squares, 0.0));
+    assert!(block.will_collide(&board, 0));
 }
 
 #[test]
 fn block_validate_cannot_move_horizontally_left_border() {
-    let mut block = Block::new(BlockType::Z, Configuration::new());
-    block.translate(0.0, 0.0);
+    let mut block = Block::new(BlockType::Z, Configuration::default(), graphics::WHITE);
+    block.translate(0, 0);
 
-    assert!(block.will_collide(&Vec::new(), -1.0));
+    assert!(block.will_collide(&Board::new(10, 18), -1));
 }
 
 #[test]
 fn block_validate_cannot_move_horizontally_right_border() {
-    let mut block = Block::new(BlockType::Z, Configuration::new());
-    block.translate(BOARD_WIDTH / SQUARE_SIZE, 0.0);
+    let configuration = Configuration::default();
+    let board = Board::new(configuration.board_width(), configuration.board_height());
+    let mut block = Block::new(BlockType::Z, configuration, graphics::WHITE);
+    block.translate(board.width() as i32, 0);
 
-    assert!(block.will_collide(&Vec::new(), 1.0));
+    assert!(block.will_collide(&board, 1));
 }
 
 #[test]
 fn block_validate_cannot_move_horizontally_another_square() {
-    let mut block = Block::new(BlockType::Z, Configuration::new());
-    block.translate(1.0, 15.0);
+    let mut block = Block::new(BlockType::Z, Configuration::default(), graphics::WHITE);
+    block.translate(1, 15);
 
-    let squares = vec!(Square::new(15.0, 0.0, graphics::BLACK));
+    let mut board = Board::new(10, 18);
+    board.set(15, 0, Cell { color: graphics::BLACK, block_type: BlockType::I });
 
-    assert!(block.will_collide(&squares, -1.0));
+    assert!(block.will_collide(&board, -1));
 }
 
 #[test]
 fn block_validate_rotation_z() {
-    let mut block = Block::new(BlockType::Z, Configuration::new());
-    block.rotate();
+    let block = Block::new(BlockType::Z, Configuration::default(), graphics::WHITE);
+    let positions = block.positions_for(RotationState::Right);
 
-    assert!(block.positions.iter().any(|p| *p == (0.0, 2.0)));
-    assert!(block.positions.iter().any(|p| *p == (1.0, 1.0)));
-    assert!(block.positions.iter().any(|p| *p == (2.0, 1.0)));
-    assert!(block.positions.iter().any(|p| *p == (1.0, 2.0)));
+    assert!(positions.iter().any(|p| *p == (2, 0)));
+    assert!(positions.iter().any(|p| *p == (2, 1)));
+    assert!(positions.iter().any(|p| *p == (1, 1)));
+    assert!(positions.iter().any(|p| *p == (1, 2)));
 }
 
 #[test]
 fn block_validate_rotation_j() {
-    let mut block = Block::new(BlockType::J, Configuration::new());
-    block.rotate();
+    let block = Block::new(BlockType::J, Configuration::default(), graphics::WHITE);
+    let positions = block.positions_for(RotationState::Right);
 
-    assert!(block.positions.iter().any(|p| *p == (0.0, 0.0)));
-    assert!(block.positions.iter().any(|p| *p == (1.0, 0.0)));
-    assert!(block.positions.iter().any(|p| *p == (1.0, 1.0)));
-    assert!(block.positions.iter().any(|p| *p == (1.0, 2.0)));
+    assert!(positions.iter().any(|p| *p == (1, 0)));
+    assert!(positions.iter().any(|p| *p == (2, 0)));
+    assert!(positions.iter().any(|p| *p == (1, 1)));
+    assert!(positions.iter().any(|p| *p == (1, 2)));
 }
\ No newline at end of file
@@ -0,0 +1,74 @@
+use tetris::block::*;
+use tetris::board::*;
+use ggez::graphics;
+
+fn cell() -> Cell {
+    Cell { color: graphics::WHITE, block_type: BlockType::O }
+}
+
+fn fill_row(board: &mut Board, row: i32) {
+    for column in 0..board.width() as i32 {
+        board.set(row, column, cell());
+    }
+}
+
+#[test]
+fn board_validate_occupancy() {
+    let mut board = Board::new(10, 18);
+    board.set(17, 3, cell());
+
+    assert!(board.is_occupied(17, 3));
+    assert!(!board.is_occupied(17, 4));
+    assert_eq!(board.row_count(17), 1);
+}
+
+#[test]
+fn board_validate_walls_and_floor_are_blocked() {
+    let board = Board::new(10, 18);
+
+    assert!(board.is_blocked(0, -1));
+    assert!(board.is_blocked(0, 10));
+    assert!(board.is_blocked(18, 0));
+    assert!(!board.is_blocked(-1, 0));
+}
+
+#[test]
+fn board_validate_clear_full_rows() {
+    let mut board = Board::new(10, 18);
+    fill_row(&mut board, 17);
+    fill_row(&mut board, 16);
+    board.set(15, 2, cell());
+
+    assert!(board.is_row_full(17));
+    assert_eq!(board.clear_full_rows(), 2);
+    assert!(board.is_occupied(17, 2));
+    assert_eq!(board.row_count(17), 1);
+    assert_eq!(board.row_count(15), 0);
+}
+
+#[test]
+fn board_validate_clear_cell_updates_row() {
+    let mut board = Board::new(4, 4);
+    fill_row(&mut board, 3);
+    board.clear(3, 1);
+
+    assert!(!board.is_row_full(3));
+    assert_eq!(board.clear_full_rows(), 0);
+    assert_eq!(board.cells().count(), 3);
+}
+
+#[test]
+fn board_hidden_rows_keep_squares_above_the_playfield() {
+    let mut board = Board::new(4, 4);
+    fill_row(&mut board, 3);
+    board.set(-1, 2, cell());
+
+    assert!(board.is_occupied(-1, 2));
+    assert!(!board.is_inside(-1, 2));
+    assert!(board.is_blocked(-(Board::BUFFER_ROWS as i32) - 1, 0));
+    assert_eq!(board.cells().filter(|(row, _, _)| *row < 0).count(), 1);
+
+    assert_eq!(board.clear_full_rows(), 1);
+    assert!(board.is_occupied(0, 2));
+    assert_eq!(board.row_count(0), 1);
+}
\ No newline at end of file
@@ -0,0 +1,36 @@
+use tetris::board::*;
+use tetris::bomb::*;
+
+fn sorted(mut cells: Vec<(i32, i32)>) -> Vec<(i32, i32)> {
+    cells.sort();
+    cells
+}
+
+#[test]
+fn bomb_blast_patterns_cover_their_cells() {
+    let board = Board::new(10, 18);
+
+    assert_eq!(BlastPattern::Square.cells(&board, 5, 5).len(), 9);
+    assert_eq!(sorted(BlastPattern::Plus.cells(&board, 5, 5)), vec!((3, 5), (4, 5), (5, 3), (5, 4), (5, 5), (5, 6), (5, 7), (6, 5), (7, 5)));
+    assert_eq!(BlastPattern::Row.cells(&board, 5, 5), (0..10).map(|column| (5, column)).collect::<Vec<(i32, i32)>>());
+    assert_eq!(BlastPattern::Column.cells(&board, 5, 5), (0..18).map(|row| (row, 5)).collect::<Vec<(i32, i32)>>());
+    assert_eq!(BlastPattern::Radius(1).cells(&board, 5, 5).len(), 5);
+    assert_eq!(BlastPattern::Radius(2).cells(&board, 5, 5).len(), 13);
+}
+
+#[test]
+fn bomb_blast_stays_inside_the_board() {
+    let board = Board::new(10, 18);
+
+    assert_eq!(sorted(BlastPattern::Square.cells(&board, 0, 0)), vec!((0, 0), (0, 1), (1, 0), (1, 1)));
+    assert!(BlastPattern::Radius(3).cells(&board, 17, 9).iter().all(|(row, column)| board.is_inside(*row, *column)));
+}
+
+#[test]
+fn bomb_blast_patterns_are_read_from_text() {
+    assert_eq!("radius_2".parse::<BlastPattern>(), Ok(BlastPattern::Radius(2)));
+    assert_eq!("plus".parse::<BlastPattern>(), Ok(BlastPattern::Plus));
+    assert!("radius_0".parse::<BlastPattern>().is_err());
+    assert!("cross".parse::<BlastPattern>().is_err());
+    assert_eq!(BlastPattern::Radius(3).to_string(), "radius_3");
+}
\ No newline at end of file
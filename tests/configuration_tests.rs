@@ -0,0 +1,99 @@
+use tetris::bomb::*;
+use tetris::configuration::*;
+use tetris::gravity::*;
+use tetris::items::*;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+fn write_config(name: &str, contents: &str) -> PathBuf {
+    let path = env::temp_dir().join(format!("tetris_{}_{}.ini", name, std::process::id()));
+    fs::write(&path, contents).unwrap();
+
+    path
+}
+
+#[test]
+fn configuration_missing_keys_use_defaults() {
+    let path = write_config("partial", "[game]\nclassic_mode = false\n");
+    let configuration = Configuration::load(Some(&path)).unwrap();
+    fs::remove_file(&path).unwrap();
+
+    assert!(!configuration.classic_mode());
+    assert_eq!(configuration.board_width(), 10);
+    assert_eq!(configuration.board_height(), 18);
+    assert_eq!(configuration.viewing_area_rows_count(), 10);
+}
+
+#[test]
+fn configuration_reads_board_dimensions() {
+    let path = write_config("board", "[board]\nwidth = 4\nheight = 20\n");
+    let configuration = Configuration::load(Some(&path)).unwrap();
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(configuration.board_width(), 4);
+    assert_eq!(configuration.board_height(), 20);
+}
+
+#[test]
+fn configuration_invalid_value_names_the_key() {
+    let path = write_config("invalid", "[game]\ndefault_speed = fast\n");
+    let result = Configuration::load(Some(&path));
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(result.err(), Some(ConfigError::InvalidValue {
+        key: String::from("game.default_speed"),
+        value: String::from("fast")
+    }));
+}
+
+#[test]
+fn configuration_missing_file_is_an_error() {
+    let path = env::temp_dir().join("tetris_does_not_exist.ini");
+
+    assert_eq!(Configuration::load(Some(&path)).err(), Some(ConfigError::NotFound(path)));
+}
+
+#[test]
+fn configuration_unreadable_file_is_an_error() {
+    let path = env::temp_dir().join(format!("tetris_unreadable_{}.ini", std::process::id()));
+    fs::write(&path, [0xff, 0xfe, 0x5b, 0x0a]).unwrap();
+    let result = Configuration::load(Some(&path));
+    fs::remove_file(&path).unwrap();
+
+    match result {
+        Err(ConfigError::ReadFailed { path: failed_path, .. }) => assert_eq!(failed_path, path),
+        other => panic!("expected a read error, got {:?}", other)
+    }
+}
+
+#[test]
+fn configuration_save_writes_a_file_that_loads_back() {
+    let mut configuration = Configuration::default();
+    configuration.set_classic_mode(false);
+    configuration.set_starting_level(7);
+    configuration.set_preview_count(5);
+    configuration.set_seed(Some(42));
+    configuration.set_blast_patterns(vec!(BlastPattern::Plus, BlastPattern::Radius(2)));
+    configuration.set_explosion_gravity(ExplosionGravity::Cascade);
+    configuration.set_item_weight(ItemKind::ColorBomb, 0);
+
+    let path = env::temp_dir().join(format!("tetris_save_{}", std::process::id())).join("conf.ini");
+    configuration.save(&path).unwrap();
+    let loaded = Configuration::load(Some(&path)).unwrap();
+    fs::remove_dir_all(path.parent().unwrap()).unwrap();
+
+    assert_eq!(loaded, configuration);
+}
+
+#[test]
+fn configuration_starting_level_is_checked() {
+    let path = write_config("starting_level", "[game]\nstarting_level = 0\n");
+    let result = Configuration::load(Some(&path));
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(result.err(), Some(ConfigError::InvalidValue {
+        key: String::from("game.starting_level"),
+        value: String::from("0")
+    }));
+}
@@ -0,0 +1,372 @@
+use tetris::engine::*;
+use tetris::input::*;
+use tetris::configuration::*;
+use tetris::items::*;
+use tetris::rotation::*;
+
+const STEP: f32 = 1.0 / 60.0;
+
+#[test]
+fn game_current_block_falls_without_input() {
+    let mut game = Game::new(Configuration::default());
+    let start_row = game.current_block().translate.1;
+
+    game.step(STEP, &Input::default());
+    assert!(game.fall_progress() > 0.0);
+    assert_eq!(game.current_block().translate.1, start_row);
+
+    for _ in 0..60 {
+        game.step(STEP, &Input::default());
+    }
+    assert!(game.current_block().translate.1 > start_row);
+}
+
+#[test]
+fn game_locks_block_on_the_floor() {
+    let mut game = Game::new(Configuration::default());
+    let input = Input::default();
+
+    while game.board().is_empty() {
+        game.step(STEP, &input);
+    }
+
+    assert!(game.board().row_count(17) > 0);
+    assert!(!game.game_over());
+}
+
+#[test]
+fn game_ends_when_the_stack_reaches_the_top() {
+    let mut game = Game::new(Configuration::default());
+    let input = Input::default();
+
+    for _ in 0..100000 {
+        if game.game_over() {
+            break;
+        }
+
+        game.step(STEP, &input);
+    }
+
+    assert!(game.game_over());
+    assert_eq!(game.top_out(), Some(TopOut::BlockOut));
+}
+
+#[test]
+fn game_keeps_stats_until_the_game_is_over() {
+    let mut game = Game::new(Configuration::default());
+    let hard_drop = Input { hard_drop: true, .. Default::default() };
+
+    for _ in 0..3 {
+        game.step(STEP, &hard_drop);
+    }
+    assert_eq!(game.pieces(), 3);
+
+    while !game.game_over() {
+        game.step(STEP, &hard_drop);
+    }
+
+    let time_played = game.time_played();
+    game.step(STEP, &hard_drop);
+    assert_eq!(game.time_played(), time_played);
+    assert_eq!(game.total_lines(), 0);
+}
+
+#[test]
+fn game_hold_swaps_current_block_once_per_drop() {
+    let mut game = Game::new(Configuration::default());
+    let first_block = game.current_block().block_type();
+    let next_block = game.next_pieces()[0].block().unwrap().block_type();
+    let hold = Input { hold: true, .. Default::default() };
+
+    game.step(STEP, &hold);
+
+    assert_eq!(game.hold_block().map(|b| b.block_type()), Some(first_block));
+    assert_eq!(game.current_block().block_type(), next_block);
+    assert!(!game.can_hold());
+
+    game.step(STEP, &hold);
+
+    assert_eq!(game.hold_block().map(|b| b.block_type()), Some(first_block));
+    assert_eq!(game.current_block().block_type(), next_block);
+}
+
+#[test]
+fn game_next_queue_has_the_configured_length() {
+    let game = Game::new(Configuration::default());
+
+    assert_eq!(game.next_pieces().len(), Configuration::default().preview_count());
+}
+
+#[test]
+fn game_with_the_same_seed_plays_out_the_same() {
+    let mut configuration = Configuration::default();
+    configuration.set_seed(Some(42));
+
+    let mut first = Game::new(configuration.clone());
+    let mut second = Game::new(configuration);
+    let input = Input { movement: 1.0, .. Default::default() };
+
+    for _ in 0..2000 {
+        first.step(STEP, &input);
+        second.step(STEP, &input);
+    }
+
+    assert_eq!(first.seed(), 42);
+    assert_eq!(first.current_block().block_type(), second.current_block().block_type());
+    assert_eq!(first.current_block().color(), second.current_block().color());
+    assert_eq!(first.board().cells().collect::<Vec<_>>(), second.board().cells().collect::<Vec<_>>());
+}
+
+#[test]
+fn game_rotates_once_per_press() {
+    let mut game = Game::new(Configuration::default());
+    let state = game.current_block().rotation_state();
+    let rotate = Input { rotate: Some(Rotation::Clockwise), .. Default::default() };
+
+    game.step(STEP, &rotate);
+    assert_eq!(game.current_block().rotation_state(), state.rotated(Rotation::Clockwise));
+
+    game.step(STEP, &Input::default());
+    game.step(STEP, &rotate);
+    assert_eq!(game.current_block().rotation_state(), state.rotated(Rotation::Half));
+}
+
+#[test]
+fn game_hard_drop_locks_immediately_and_scores_per_row() {
+    let mut game = Game::new(Configuration::default());
+    let distance = game.current_block().drop_distance(game.board());
+    let hard_drop = Input { hard_drop: true, .. Default::default() };
+
+    game.step(STEP, &hard_drop);
+
+    assert!(!game.board().is_empty());
+    assert_eq!(game.score(), distance * 2);
+}
+
+#[test]
+fn game_soft_drop_falls_faster_and_scores_per_row() {
+    let mut dropping = Game::new(Configuration::default());
+    let mut falling = Game::new(Configuration::default());
+    let soft_drop = Input { soft_drop: true, .. Default::default() };
+
+    for _ in 0..30 {
+        dropping.step(STEP, &soft_drop);
+        falling.step(STEP, &Input::default());
+    }
+
+    assert!(dropping.current_block().translate.1 > falling.current_block().translate.1);
+    assert_eq!(dropping.score(), dropping.current_block().translate.1);
+}
+
+#[test]
+fn game_ghost_block_rests_on_the_floor() {
+    let game = Game::new(Configuration::default());
+    let ghost_block = game.ghost_block();
+
+    let lowest_row = ghost_block.to_cells().iter().map(|(row, _)| *row).max().unwrap();
+
+    assert_eq!(lowest_row, game.board().height() as i32 - 1);
+    assert_eq!(ghost_block.drop_distance(game.board()), 0);
+}
+
+fn fall_to_the_floor(game: &mut Game) {
+    while !game.current_block().will_collide(game.board(), 0) {
+        game.step(STEP, &Input::default());
+    }
+}
+
+#[test]
+fn game_lock_delay_waits_before_locking() {
+    let mut game = Game::new(Configuration::default());
+    fall_to_the_floor(&mut game);
+
+    for _ in 0..25 {
+        game.step(STEP, &Input::default());
+    }
+    assert!(game.board().is_empty());
+
+    for _ in 0..10 {
+        game.step(STEP, &Input::default());
+    }
+    assert!(!game.board().is_empty());
+}
+
+#[test]
+fn game_lock_delay_restarts_on_moves() {
+    let mut game = Game::new(Configuration::default());
+    fall_to_the_floor(&mut game);
+
+    for i in 0..60 {
+        let movement = if (i / 5) % 2 == 0 { 1.0 } else { -1.0 };
+        game.step(STEP, &Input { movement, .. Default::default() });
+    }
+
+    assert!(game.board().is_empty());
+}
+
+fn game_with_controls(name: &str, das_ms: u32, arr_ms: u32) -> Game {
+    let path = std::env::temp_dir().join(format!("tetris_{}_{}.ini", name, std::process::id()));
+    std::fs::write(&path, format!("[controls]\ndas_ms = {}\narr_ms = {}\n[game]\nseed = 7\n", das_ms, arr_ms)).unwrap();
+    let configuration = Configuration::load(Some(&path)).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    Game::new(configuration)
+}
+
+fn hold_right(game: &mut Game, dt: f32, seconds: f32) {
+    let steps = (seconds / dt).round() as i32;
+    for _ in 0..steps {
+        game.step(dt, &Input { movement: 1.0, .. Default::default() });
+    }
+}
+
+#[test]
+fn game_tap_moves_exactly_one_column() {
+    let mut game = game_with_controls("tap", 170, 50);
+    let column = game.current_block().translate.0;
+
+    hold_right(&mut game, STEP, STEP * 3.0);
+    game.step(STEP, &Input::default());
+
+    assert_eq!(game.current_block().translate.0, column + 1);
+}
+
+#[test]
+fn game_tap_released_before_the_step_still_moves() {
+    let mut game = game_with_controls("released_tap", 170, 50);
+    let column = game.current_block().translate.0;
+
+    game.step(STEP, &Input { tap: 1, .. Default::default() });
+    assert_eq!(game.current_block().translate.0, column + 1);
+
+    game.step(STEP, &Input { movement: 1.0, tap: 1, .. Default::default() });
+    hold_right(&mut game, STEP, STEP * 3.0);
+    assert_eq!(game.current_block().translate.0, column + 2);
+}
+
+#[test]
+fn game_zero_arr_slides_to_the_wall_after_das() {
+    let mut game = game_with_controls("arr0", 100, 0);
+    let column = game.current_block().translate.0;
+
+    hold_right(&mut game, STEP, 0.05);
+    assert_eq!(game.current_block().translate.0, column + 1);
+
+    hold_right(&mut game, STEP, 0.1);
+    assert!(!game.current_block().fits(game.board(), 1, 0));
+}
+
+#[test]
+fn game_auto_shift_does_not_depend_on_the_frame_rate() {
+    let mut slow = game_with_controls("fps60", 100, 100);
+    let mut fast = game_with_controls("fps120", 100, 100);
+
+    hold_right(&mut slow, 1.0 / 60.0, 0.25);
+    hold_right(&mut fast, 1.0 / 120.0, 0.25);
+
+    assert_eq!(slow.current_block().translate.0, fast.current_block().translate.0);
+}
+
+
+#[test]
+fn game_starts_on_the_configured_level() {
+    let mut configuration = Configuration::default();
+    configuration.set_starting_level(5);
+
+    assert_eq!(Game::new(configuration).level(), 5);
+}
+
+
+/// A viewing-area configuration where every queued piece is an item of `kind`.
+fn items_configuration(kind: ItemKind) -> Configuration {
+    let mut configuration = Configuration::default();
+    configuration.set_classic_mode(false);
+    configuration.set_item_chance(1.0);
+    for other in ItemKind::all() {
+        configuration.set_item_weight(other, if other == kind { 1 } else { 0 });
+    }
+
+    configuration
+}
+
+fn game_with_only(kind: ItemKind) -> Game {
+    Game::new(items_configuration(kind))
+}
+
+#[test]
+fn game_items_are_decided_when_the_queue_is_dealt() {
+    let game = game_with_only(ItemKind::ColumnDrill);
+    assert!(game.next_pieces().iter().all(|piece| piece.item() == Some(SpecialItem::ColumnDrill)));
+
+    let mut configuration = Configuration::default();
+    configuration.set_item_chance(1.0);
+    let classic = Game::new(configuration);
+    assert!(classic.next_pieces().iter().all(|piece| piece.block().is_some()));
+}
+
+#[test]
+fn game_counts_items_used_and_squares_destroyed() {
+    let mut game = game_with_only(ItemKind::ColumnDrill);
+    game.step(STEP, &Input { hard_drop: true, .. Default::default() });
+    assert!(game.item().is_some());
+
+    let soft_drop = Input { soft_drop: true, .. Default::default() };
+    for _ in 0..1000 {
+        if game.items_used(ItemKind::ColumnDrill) > 0 {
+            break;
+        }
+
+        game.step(STEP, &soft_drop);
+    }
+
+    assert_eq!(game.items_used(ItemKind::ColumnDrill), 1);
+    assert_eq!(game.items_used(ItemKind::Bomb), 0);
+    assert!(game.squares_destroyed() > 0);
+}
+
+fn game_with_a_falling_bomb(fuse_ms: u32) -> Game {
+    let mut configuration = items_configuration(ItemKind::Bomb);
+    configuration.set_fuse_ms(fuse_ms);
+    let mut game = Game::new(configuration);
+
+    game.step(STEP, &Input { hard_drop: true, .. Default::default() });
+    assert!(game.item().is_some());
+
+    game
+}
+
+#[test]
+fn game_bomb_can_be_detonated_in_mid_air() {
+    let mut game = game_with_a_falling_bomb(0);
+    game.step(STEP, &Input { detonate: true, .. Default::default() });
+
+    assert_eq!(game.items_used(ItemKind::Bomb), 1);
+    assert_eq!(game.squares_destroyed(), 0);
+}
+
+#[test]
+fn game_bomb_hard_drop_explodes_where_it_lands() {
+    let mut game = game_with_a_falling_bomb(0);
+    let score = game.score();
+    game.step(STEP, &Input { hard_drop: true, .. Default::default() });
+
+    assert_eq!(game.items_used(ItemKind::Bomb), 1);
+    assert!(game.squares_destroyed() > 0);
+    assert!(game.score() > score);
+}
+
+#[test]
+fn game_bomb_goes_off_when_the_fuse_burns_out() {
+    let mut game = game_with_a_falling_bomb(500);
+    assert_eq!(game.fuse_left(), Some(0.5));
+
+    for _ in 0..25 {
+        game.step(STEP, &Input::default());
+    }
+    assert_eq!(game.items_used(ItemKind::Bomb), 0);
+
+    for _ in 0..10 {
+        game.step(STEP, &Input::default());
+    }
+    assert_eq!(game.items_used(ItemKind::Bomb), 1);
+}
\ No newline at end of file
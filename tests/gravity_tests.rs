@@ -0,0 +1,60 @@
+use tetris::block::*;
+use tetris::board::*;
+use tetris::gravity::*;
+use ggez::graphics;
+
+fn cell() -> Cell {
+    Cell { color: graphics::WHITE, block_type: BlockType::O }
+}
+
+fn board_with(cells: &[(i32, i32)]) -> Board {
+    let mut board = Board::new(6, 8);
+    for (row, column) in cells {
+        board.set(*row, *column, cell());
+    }
+
+    board
+}
+
+fn occupied(board: &Board) -> Vec<(i32, i32)> {
+    let mut cells: Vec<(i32, i32)> = board.cells().map(|(row, column, _)| (row, column)).collect();
+    cells.sort();
+    cells
+}
+
+#[test]
+fn gravity_none_leaves_squares_floating() {
+    let mut board = board_with(&[(4, 1), (7, 1)]);
+    ExplosionGravity::None.apply(&mut board, &[(5, 1), (6, 1)]);
+
+    assert_eq!(occupied(&board), vec!((4, 1), (7, 1)));
+}
+
+#[test]
+fn gravity_naive_closes_the_holes_of_each_column() {
+    // Column 1 lost rows 5 and 6, column 2 lost row 6; the square at (3, 3) floats over an older hole
+    let mut board = board_with(&[(3, 1), (4, 1), (7, 1), (5, 2), (7, 2), (3, 3), (7, 3)]);
+    ExplosionGravity::Naive.apply(&mut board, &[(5, 1), (6, 1), (6, 2)]);
+
+    assert_eq!(occupied(&board), vec!((3, 3), (5, 1), (6, 1), (6, 2), (7, 1), (7, 2), (7, 3)));
+}
+
+#[test]
+fn gravity_cascade_drops_chunks_until_they_land() {
+    // An L-shaped chunk hanging over the floor and a lone square above it
+    let mut board = board_with(&[(1, 3), (4, 2), (4, 3), (3, 3), (7, 0)]);
+    ExplosionGravity::Cascade.apply(&mut board, &[]);
+
+    assert_eq!(occupied(&board), vec!((5, 3), (6, 3), (7, 0), (7, 2), (7, 3)));
+}
+
+#[test]
+fn gravity_settle_only_moves_squares_for_cascade() {
+    let mut naive = board_with(&[(2, 0)]);
+    ExplosionGravity::Naive.settle(&mut naive);
+    assert_eq!(occupied(&naive), vec!((2, 0)));
+
+    let mut cascade = board_with(&[(2, 0)]);
+    ExplosionGravity::Cascade.settle(&mut cascade);
+    assert_eq!(occupied(&cascade), vec!((7, 0)));
+}
\ No newline at end of file
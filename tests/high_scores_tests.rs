@@ -0,0 +1,63 @@
+use tetris::configuration::*;
+use tetris::high_scores::*;
+use std::env;
+
+#[test]
+fn high_scores_keep_the_best_scores_in_order() {
+    let mut high_scores = HighScores::new();
+
+    assert_eq!(high_scores.insert(ScoreMode::Classic, HighScore::new("AAA", 300, 2, 12)), Some(0));
+    assert_eq!(high_scores.insert(ScoreMode::Classic, HighScore::new("BBB", 500, 3, 20)), Some(0));
+    assert_eq!(high_scores.insert(ScoreMode::Classic, HighScore::new("CCC", 300, 2, 10)), Some(2));
+
+    let names: Vec<&str> = high_scores.entries(ScoreMode::Classic).iter().map(|high_score| high_score.name()).collect();
+    assert_eq!(names, vec!("BBB", "AAA", "CCC"));
+    assert!(high_scores.entries(ScoreMode::ViewingArea(10)).is_empty());
+}
+
+#[test]
+fn high_scores_tables_are_capped() {
+    let mut high_scores = HighScores::new();
+    for score in 1..=HighScores::MAX_ENTRIES as i32 {
+        high_scores.insert(ScoreMode::Classic, HighScore::new("AAA", score * 100, 1, 0));
+    }
+
+    assert!(!high_scores.qualifies(ScoreMode::Classic, 100));
+    assert_eq!(high_scores.insert(ScoreMode::Classic, HighScore::new("BBB", 50, 1, 0)), None);
+    assert_eq!(high_scores.insert(ScoreMode::Classic, HighScore::new("BBB", 150, 1, 0)), Some(HighScores::MAX_ENTRIES - 1));
+    assert_eq!(high_scores.entries(ScoreMode::Classic).len(), HighScores::MAX_ENTRIES);
+    assert_eq!(high_scores.entries(ScoreMode::Classic).last().unwrap().score(), 150);
+}
+
+#[test]
+fn high_scores_mode_depends_on_the_viewing_area() {
+    let mut configuration = Configuration::default();
+    assert_eq!(ScoreMode::of(&configuration), ScoreMode::Classic);
+
+    configuration.set_classic_mode(false);
+    assert_eq!(ScoreMode::of(&configuration), ScoreMode::ViewingArea(configuration.viewing_area_rows_count()));
+}
+
+#[test]
+fn high_scores_save_writes_a_file_that_loads_back() {
+    let mut high_scores = HighScores::new();
+    high_scores.insert(ScoreMode::Classic, HighScore::new("AAA", 1200, 4, 31));
+    high_scores.insert(ScoreMode::ViewingArea(8), HighScore::new("BBB", 900, 2, 15));
+
+    let path = env::temp_dir().join(format!("tetris_high_scores_{}", std::process::id())).join(HighScores::FILE_NAME);
+    high_scores.save(&path).unwrap();
+    let loaded = HighScores::load(&path);
+    std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+
+    assert_eq!(loaded, high_scores);
+}
+
+#[test]
+fn high_scores_corrupt_or_unknown_files_are_ignored() {
+    assert_eq!(HighScores::parse("\u{0}garbage\n[classic]\n1, 2"), HighScores::new());
+    assert_eq!(HighScores::parse("version = 99\n[classic]\n100, 1, 1, AAA\n"), HighScores::new());
+    assert_eq!(HighScores::load(&env::temp_dir().join("tetris_no_high_scores.txt")), HighScores::new());
+
+    let high_scores = HighScores::parse("version = 1\n[classic]\n100, 1, 1, AAA\nbroken line\n200, x, 1, BBB\n[unknown]\n300, 1, 1, CCC\n");
+    assert_eq!(high_scores.entries(ScoreMode::Classic), &[HighScore::new("AAA", 100, 1, 1)]);
+}
\ No newline at end of file
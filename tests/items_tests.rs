@@ -0,0 +1,60 @@
+use tetris::block::*;
+use tetris::board::*;
+use tetris::bomb::*;
+use tetris::items::*;
+
+fn cell(block_type: BlockType) -> Cell {
+    Cell { color: Block::color_of(block_type), block_type }
+}
+
+fn sorted(mut cells: Vec<(i32, i32)>) -> Vec<(i32, i32)> {
+    cells.sort();
+    cells
+}
+
+#[test]
+fn items_fall_on_the_grid_and_rest_on_the_stack() {
+    let mut board = Board::new(10, 18);
+    board.set(5, 2, cell(BlockType::O));
+    let mut item = FallingItem::new(SpecialItem::Bomb(BlastPattern::Square));
+    item.translate(2, 3);
+
+    assert_eq!((item.row(), item.column()), (3, 2));
+    assert!(!item.will_collide(&board, 1));
+
+    item.translate(0, 1);
+    assert!(item.will_collide(&board, 1));
+    assert!(!item.fits(&board, 0, 1));
+    assert!(!item.fits(&board, -3, 0));
+}
+
+#[test]
+fn items_line_eraser_and_column_drill_targets() {
+    let board = Board::new(6, 8);
+
+    assert_eq!(SpecialItem::LineEraser.targets(&board, 4, 2), (0..6).map(|column| (5, column)).collect::<Vec<(i32, i32)>>());
+    assert_eq!(SpecialItem::LineEraser.targets(&board, 7, 2), (0..6).map(|column| (7, column)).collect::<Vec<(i32, i32)>>());
+    assert_eq!(SpecialItem::ColumnDrill.targets(&board, 5, 3), vec!((5, 3), (6, 3), (7, 3)));
+    assert!(SpecialItem::SlowTime.targets(&board, 5, 3).is_empty());
+}
+
+#[test]
+fn items_color_bomb_removes_the_squares_it_lands_on() {
+    let mut board = Board::new(6, 8);
+    board.set(7, 0, cell(BlockType::T));
+    board.set(7, 1, cell(BlockType::S));
+    board.set(6, 4, cell(BlockType::T));
+
+    assert_eq!(sorted(SpecialItem::ColorBomb.targets(&board, 6, 0)), vec!((6, 4), (7, 0)));
+    assert!(SpecialItem::ColorBomb.targets(&board, 7, 3).is_empty());
+}
+
+#[test]
+fn items_kinds_are_read_from_text() {
+    for kind in ItemKind::all() {
+        assert_eq!(kind.to_string().parse::<ItemKind>(), Ok(kind));
+    }
+
+    assert_eq!(SpecialItem::Bomb(BlastPattern::Plus).kind(), ItemKind::Bomb);
+    assert!("rocket".parse::<ItemKind>().is_err());
+}
\ No newline at end of file
@@ -0,0 +1,53 @@
+use tetris::block::*;
+use tetris::randomizer::*;
+use rand::{ SeedableRng, rngs::StdRng };
+
+fn deal(randomizer: &mut dyn Randomizer, count: usize) -> Vec<BlockType> {
+    let mut rng = StdRng::seed_from_u64(7);
+    (0..count).map(|_| randomizer.next(&mut rng)).collect()
+}
+
+fn count(pieces: &[BlockType], block_type: BlockType) -> usize {
+    pieces.iter().filter(|p| **p == block_type).count()
+}
+
+#[test]
+fn randomizer_bag7_deals_every_piece_once_per_bag() {
+    let mut randomizer = RandomizerKind::Bag7.create(PieceSet::Classic);
+    let pieces = deal(randomizer.as_mut(), 14);
+
+    for block_type in PieceSet::Classic.pieces() {
+        assert_eq!(count(&pieces[0..7], block_type), 1);
+        assert_eq!(count(&pieces[7..14], block_type), 1);
+    }
+}
+
+#[test]
+fn randomizer_bag14_deals_every_piece_twice_per_bag() {
+    let mut randomizer = RandomizerKind::Bag14.create(PieceSet::Classic);
+    let pieces = deal(randomizer.as_mut(), 14);
+
+    for block_type in PieceSet::Classic.pieces() {
+        assert_eq!(count(&pieces, block_type), 2);
+    }
+}
+
+#[test]
+fn randomizer_bag_respects_the_extended_piece_set() {
+    let mut randomizer = RandomizerKind::Bag7.create(PieceSet::Extended);
+    let pieces = deal(randomizer.as_mut(), 9);
+
+    assert_eq!(count(&pieces, BlockType::Plus), 1);
+    assert_eq!(count(&pieces, BlockType::BigZ), 1);
+}
+
+#[test]
+fn randomizer_classic_set_never_deals_extended_pieces() {
+    for kind in [RandomizerKind::Uniform, RandomizerKind::Nes].iter() {
+        let mut randomizer = kind.create(PieceSet::Classic);
+        let pieces = deal(randomizer.as_mut(), 500);
+
+        assert_eq!(count(&pieces, BlockType::Plus), 0);
+        assert_eq!(count(&pieces, BlockType::BigZ), 0);
+    }
+}
\ No newline at end of file
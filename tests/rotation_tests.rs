@@ -0,0 +1,68 @@
+use tetris::block::*;
+use tetris::board::*;
+use tetris::rotation::*;
+use tetris::configuration::*;
+use ggez::graphics;
+
+fn cell() -> Cell {
+    Cell { color: graphics::WHITE, block_type: BlockType::O }
+}
+
+#[test]
+fn rotation_clockwise_in_open_space_uses_no_kick() {
+    let mut block = Block::new(BlockType::T, Configuration::default(), graphics::WHITE);
+    block.translate(4, 5);
+
+    let placement = try_rotate(&block, Rotation::Clockwise, &Board::new(10, 18)).unwrap();
+
+    assert_eq!(placement.state, RotationState::Right);
+    assert_eq!(placement.translate, (4, 5));
+    assert_eq!(placement.kick, 0);
+}
+
+#[test]
+fn rotation_counter_clockwise_and_half_states() {
+    let block = Block::new(BlockType::L, Configuration::default(), graphics::WHITE);
+
+    assert_eq!(block.rotation_state().rotated(Rotation::CounterClockwise), RotationState::Left);
+    assert_eq!(block.rotation_state().rotated(Rotation::Half), RotationState::Reverse);
+    assert_eq!(RotationState::Left.rotated(Rotation::Clockwise), RotationState::Spawn);
+}
+
+#[test]
+fn rotation_i_kicks_off_the_left_wall() {
+    let mut block = Block::new(BlockType::I, Configuration::default(), graphics::WHITE);
+    let board = Board::new(10, 18);
+    block.translate(-1, 5);
+    block.place(Placement { state: RotationState::Right, translate: block.translate, kick: 0 });
+
+    // The vertical I sits in column 1 of the wall; turning back to spawn needs a kick to the right.
+    let placement = try_rotate(&block, Rotation::CounterClockwise, &board).unwrap();
+
+    assert_eq!(placement.state, RotationState::Spawn);
+    assert!(placement.kick > 0);
+    assert!(placement.translate.0 >= 0);
+}
+
+#[test]
+fn rotation_fails_when_every_kick_is_blocked() {
+    let mut board = Board::new(4, 4);
+    for row in 0..4 {
+        for column in 0..4 {
+            if row != 1 {
+                board.set(row, column, cell());
+            }
+        }
+    }
+
+    let mut block = Block::new(BlockType::I, Configuration::default(), graphics::WHITE);
+    block.translate(0, 0);
+
+    assert_eq!(try_rotate(&block, Rotation::Clockwise, &board), None);
+}
+
+#[test]
+fn rotation_plus_and_big_z_use_custom_kicks() {
+    assert_eq!(kicks(BlockType::Plus, RotationState::Spawn, Rotation::Clockwise).len(), 1);
+    assert_eq!(kicks(BlockType::BigZ, RotationState::Right, Rotation::CounterClockwise)[1], (-1, 0));
+}
\ No newline at end of file
@@ -0,0 +1,42 @@
+use tetris::constants::*;
+use tetris::scoring::*;
+use tetris::spin::*;
+
+#[test]
+fn scoring_combo_grows_with_every_clearing_lock() {
+    let mut score_keeper = ScoreKeeper::new();
+
+    assert_eq!(score_keeper.lock(1, None, false).points, SINGLE_LINE_POINTS);
+    assert_eq!(score_keeper.lock(1, None, false).points, SINGLE_LINE_POINTS + COMBO_POINTS);
+    assert_eq!(score_keeper.lock(2, None, false).points, DOUBLE_LINE_POINTS + 2 * COMBO_POINTS);
+
+    score_keeper.lock(0, None, false);
+    assert_eq!(score_keeper.combo(), 0);
+    assert_eq!(score_keeper.lock(1, None, false).combo, 0);
+}
+
+#[test]
+fn scoring_back_to_back_needs_consecutive_tetrises_or_spins() {
+    let mut score_keeper = ScoreKeeper::new();
+
+    assert!(!score_keeper.lock(4, None, false).back_to_back);
+    score_keeper.lock(0, None, false);
+    score_keeper.lock(0, Some(Spin::Full), false);
+
+    let score = score_keeper.lock(2, Some(Spin::Full), false);
+    assert!(score.back_to_back);
+    assert_eq!(score.points, (SPIN_POINTS[2] as f32 * BACK_TO_BACK_FACTOR).round() as i32);
+
+    score_keeper.lock(1, None, false);
+    assert!(!score_keeper.back_to_back());
+    assert!(!score_keeper.lock(4, None, false).back_to_back);
+}
+
+#[test]
+fn scoring_perfect_clear_adds_its_bonus() {
+    let mut score_keeper = ScoreKeeper::new();
+    let score = score_keeper.lock(4, None, true);
+
+    assert!(score.perfect_clear);
+    assert_eq!(score.points, TETRIS_POINTS + PERFECT_CLEAR_POINTS[3]);
+}
\ No newline at end of file
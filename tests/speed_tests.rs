@@ -0,0 +1,58 @@
+use tetris::speed::*;
+use tetris::configuration::*;
+use std::env;
+use std::fs;
+
+fn load_config(name: &str, contents: &str) -> Result<Configuration, ConfigError> {
+    let path = env::temp_dir().join(format!("tetris_{}_{}.ini", name, std::process::id()));
+    fs::write(&path, contents).unwrap();
+    let configuration = Configuration::load(Some(&path));
+    fs::remove_file(&path).unwrap();
+
+    configuration
+}
+
+#[test]
+fn speed_curves_get_faster_with_the_level() {
+    let configuration = Configuration::default();
+
+    for curve in [SpeedCurve::Guideline, SpeedCurve::Nes, SpeedCurve::Linear].iter() {
+        assert!(curve.gravity(10, &configuration) > curve.gravity(1, &configuration));
+    }
+}
+
+#[test]
+fn speed_guideline_stays_at_full_speed_on_high_levels() {
+    let configuration = Configuration::default();
+
+    for level in [20, 116, 117, 500].iter() {
+        assert_eq!(SpeedCurve::Guideline.gravity(*level, &configuration), 20.0);
+    }
+}
+
+#[test]
+fn speed_guideline_and_nes_starting_gravity() {
+    let configuration = Configuration::default();
+
+    assert!((SpeedCurve::Guideline.gravity(1, &configuration) - 1.0 / 60.0).abs() < 0.0001);
+    assert_eq!(SpeedCurve::Nes.gravity(1, &configuration), 1.0 / 48.0);
+    assert_eq!(SpeedCurve::Nes.gravity(40, &configuration), 1.0);
+}
+
+#[test]
+fn speed_custom_table_repeats_its_last_level() {
+    let configuration = load_config("speed_table", "[game]\nspeed_curve = custom\nspeed_table = 0.1, 0.5, 2\n").unwrap();
+
+    assert_eq!(SpeedCurve::Custom.gravity(2, &configuration), 0.5);
+    assert_eq!(SpeedCurve::Custom.gravity(9, &configuration), 2.0);
+}
+
+#[test]
+fn speed_custom_curve_needs_a_table() {
+    let result = load_config("no_speed_table", "[game]\nspeed_curve = custom\n");
+
+    assert_eq!(result.err(), Some(ConfigError::InvalidValue {
+        key: String::from("game.speed_table"),
+        value: String::new()
+    }));
+}
\ No newline at end of file
@@ -0,0 +1,101 @@
+use tetris::block::*;
+use tetris::board::*;
+use tetris::rotation::*;
+use tetris::spin::*;
+use tetris::configuration::*;
+use ggez::graphics;
+
+fn cell() -> Cell {
+    Cell { color: graphics::WHITE, block_type: BlockType::O }
+}
+
+// A T slot with an overhang on its left:
+// . . . . .
+// . X . . .
+// X . . . X
+// X X . X X
+fn t_slot() -> Board {
+    let mut board = Board::new(5, 4);
+    for (row, column) in [(1, 1), (2, 0), (2, 4), (3, 0), (3, 1), (3, 3), (3, 4)].iter() {
+        board.set(*row, *column, cell());
+    }
+
+    board
+}
+
+fn t_block(state: RotationState) -> Block {
+    let mut block = Block::new(BlockType::T, Configuration::default(), graphics::WHITE);
+    block.place(Placement { state, translate: (1, 1), kick: 0 });
+
+    block
+}
+
+#[test]
+fn spin_t_pointing_into_the_slot_is_a_full_spin() {
+    assert_eq!(t_spin(&t_block(RotationState::Reverse), &t_slot(), Rotation::Clockwise, 0), Some(Spin::Full));
+}
+
+#[test]
+fn spin_t_pointing_away_is_a_mini_spin_unless_the_last_kick_was_used() {
+    let block = t_block(RotationState::Spawn);
+
+    assert_eq!(t_spin(&block, &t_slot(), Rotation::Clockwise, 0), Some(Spin::Mini));
+    assert_eq!(t_spin(&block, &t_slot(), Rotation::Clockwise, 4), Some(Spin::Full));
+    assert_eq!(t_spin(&block, &t_slot(), Rotation::Half, 4), Some(Spin::Mini));
+}
+
+#[test]
+fn spin_t_needs_three_corners() {
+    assert_eq!(t_spin(&t_block(RotationState::Reverse), &Board::new(5, 4), Rotation::Clockwise, 0), None);
+}
+
+#[test]
+fn spin_other_blocks_need_all_spin_and_an_immobile_block() {
+    // A vertical I in a capped one column well
+    let mut board = Board::new(3, 5);
+    board.set(0, 1, cell());
+    for row in 0..5 {
+        board.set(row, 0, cell());
+        board.set(row, 2, cell());
+    }
+
+    let mut block = Block::new(BlockType::I, Configuration::default(), graphics::WHITE);
+    block.place(Placement { state: RotationState::Right, translate: (-1, 1), kick: 0 });
+
+    assert!(is_immobile(&block, &board));
+    assert_eq!(detect_spin(&block, &board, Rotation::Clockwise, 0, true), Some(Spin::Mini));
+    assert_eq!(detect_spin(&block, &board, Rotation::Clockwise, 0, false), None);
+    assert_eq!(detect_spin(&block, &Board::new(3, 5), Rotation::Clockwise, 0, true), None);
+}
+
+#[test]
+fn spin_plus_rotated_in_its_slot_scores_but_the_o_does_not() {
+    let mut plus = Block::new(BlockType::Plus, Configuration::default(), graphics::WHITE);
+    plus.place(Placement { state: RotationState::Spawn, translate: (1, 1), kick: 0 });
+
+    // Everything but the plus itself is filled, so it can only turn in place
+    let mut board = Board::new(5, 4);
+    for row in 0..4 {
+        for column in 0..5 {
+            if !plus.to_cells().contains(&(row, column)) {
+                board.set(row, column, cell());
+            }
+        }
+    }
+
+    let placement = try_rotate(&plus, Rotation::Clockwise, &board).unwrap();
+    plus.place(placement);
+
+    assert!(is_immobile(&plus, &board));
+    assert_eq!(detect_spin(&plus, &board, Rotation::Clockwise, placement.kick, true), Some(Spin::Mini));
+
+    let mut o = Block::new(BlockType::O, Configuration::default(), graphics::WHITE);
+    o.place(Placement { state: RotationState::Right, translate: (1, 2), kick: 0 });
+    let mut board = Board::new(4, 4);
+    for (row, column) in [(1, 1), (1, 2), (2, 0), (3, 0), (2, 3), (3, 3)].iter() {
+        board.set(*row, *column, cell());
+    }
+
+    assert!(is_immobile(&o, &board));
+    assert_eq!(detect_spin(&o, &board, Rotation::Clockwise, 0, true), None);
+}
\ No newline at end of file